@@ -0,0 +1,107 @@
+use std::fmt;
+
+// What a part returns. The runner decides how to print it.
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+    // Rendered picture, like the CRT of day10. One string per row.
+    Grid(Vec<String>),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(x) => write!(f, "{}", x),
+            Answer::Text(x) => write!(f, "{}", x),
+            Answer::Grid(rows) => {
+                for row in rows {
+                    writeln!(f)?;
+                    write!(f, "{}", row)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Answer {
+    // Form used when comparing against stored answers. Trailing spaces
+    // of grid rows are ignored as editors like to strip them.
+    pub fn canonical(&self) -> String {
+        match self {
+            Answer::Int(x) => x.to_string(),
+            Answer::Text(x) => x.clone(),
+            Answer::Grid(rows) => canonical_grid(&rows.join("\n")),
+        }
+    }
+
+    // Single line form for tables
+    pub fn summary(&self) -> String {
+        match self {
+            Answer::Grid(rows) => {
+                let width = rows.iter().map(|x| x.len()).max().unwrap_or(0);
+                format!("<{}x{} grid>", rows.len(), width)
+            },
+            _ => self.to_string(),
+        }
+    }
+}
+
+pub fn canonical_grid(grid: &str) -> String {
+    let rows: Vec<&str> = grid.trim_matches('\n').lines().map(|x| x.trim_end()).collect();
+    rows.join("\n")
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Int(i64::try_from(value).expect("Answer does not fit in i64"))
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(i32, u32, i64, u64, usize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(1908462u32).to_string(), "1908462");
+        assert_eq!(Answer::from(-4).to_string(), "-4");
+        assert_eq!(Answer::from("TWSGQHNHL").to_string(), "TWSGQHNHL");
+
+        let grid = Answer::Grid(vec!["#.#".to_string(), ".#.".to_string()]);
+        assert_eq!(grid.to_string(), "\n#.#\n.#.");
+    }
+
+    #[test]
+    fn test_canonical() {
+        assert_eq!(Answer::from(42).canonical(), "42");
+
+        let grid = Answer::Grid(vec!["# # ".to_string(), " #  ".to_string()]);
+        assert_eq!(grid.canonical(), "# #\n #");
+        assert_eq!(grid.canonical(), canonical_grid("\n# #\n #  \n"));
+        assert_eq!(grid.summary(), "<2x4 grid>");
+    }
+}
@@ -0,0 +1,255 @@
+use std::{fs, path::{Path, PathBuf}, time::{Duration, Instant}};
+
+use serde_json::{Value, json};
+
+use crate::params::Params;
+use crate::solution::{Day, Parsed};
+
+// A phase is flagged when its median is this much slower than the baseline
+const SLOWDOWN_RATIO: f64 = 1.2;
+
+pub fn baseline_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("bench_baseline.json")
+}
+
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn new(samples: &[Duration]) -> Self {
+        let mut samples = samples.to_vec();
+        samples.sort();
+        Stats {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct BenchResult {
+    pub day: u8,
+    // "parse", "part1" or "part2"
+    pub phase: String,
+    // Label of the input source, timings of different inputs do not compare
+    pub input: String,
+    pub runs: usize,
+    pub stats: Stats,
+}
+
+fn time<F: FnMut()>(runs: usize, mut f: F) -> Stats {
+    let mut samples = vec![];
+    for _ in 0..runs {
+        let start = Instant::now();
+        f();
+        samples.push(start.elapsed());
+    }
+    Stats::new(&samples)
+}
+
+impl BenchResult {
+    // Results with the same key are runs of the same thing
+    fn key(&self) -> (u8, &str, &str) {
+        (self.day, &self.phase, &self.input)
+    }
+}
+
+// Times parsing and every selected part separately. Parts are timed on
+// parsed, the input already parsed from data, so that their numbers do
+// not include parsing. The parameters must have passed check_dyn.
+pub fn bench_day(day: &Day, data: &str, input: &str, parsed: &Parsed, parts: &[u8], params: &Params, runs: usize) -> Vec<BenchResult> {
+    let mut results = vec![];
+    let result = |phase: String, stats| BenchResult { day: day.day, phase, input: input.to_string(), runs, stats };
+    let stats = time(runs, || { day.solver.parse_dyn(data).ok(); });
+    results.push(result("parse".to_string(), stats));
+
+    for part in parts {
+        let stats = time(runs, || { day.solver.solve_dyn(parsed, *part, params).ok(); });
+        results.push(result(format!("part{}", part), stats));
+    }
+    results
+}
+
+fn nanos(duration: Duration) -> u64 {
+    duration.as_nanos() as u64
+}
+
+pub fn to_json(results: &[BenchResult]) -> Value {
+    let results: Vec<Value> = results.iter()
+        .map(|x| json!({
+            "day": x.day,
+            "phase": x.phase,
+            "input": x.input,
+            "runs": x.runs,
+            "min_ns": nanos(x.stats.min),
+            "median_ns": nanos(x.stats.median),
+            "max_ns": nanos(x.stats.max),
+        }))
+        .collect();
+    json!({ "results": results })
+}
+
+pub fn from_json(value: &Value) -> Result<Vec<BenchResult>, String> {
+    let results = value["results"].as_array().ok_or("Missing results array")?;
+    let mut parsed = vec![];
+    for result in results {
+        let int = |key: &str| result[key].as_u64().ok_or(format!("Missing {} in {}", key, result));
+        let string = |key: &str| result[key].as_str().map(str::to_string).ok_or(format!("Missing {} in {}", key, result));
+        parsed.push(BenchResult {
+            day: int("day")? as u8,
+            phase: string("phase")?,
+            input: string("input")?,
+            runs: int("runs")? as usize,
+            stats: Stats {
+                min: Duration::from_nanos(int("min_ns")?),
+                median: Duration::from_nanos(int("median_ns")?),
+                max: Duration::from_nanos(int("max_ns")?),
+            },
+        });
+    }
+    Ok(parsed)
+}
+
+pub fn load_baseline(path: &Path) -> Result<Vec<BenchResult>, String> {
+    let data = fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let value: Value = serde_json::from_str(&data)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    from_json(&value).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// The baseline with results replacing the entries they were compared
+// against, so that benchmarking a few days keeps the others
+pub fn merge(baseline: &[BenchResult], results: &[BenchResult]) -> Vec<BenchResult> {
+    let mut merged: Vec<BenchResult> = baseline.iter()
+        .filter(|x| !results.iter().any(|y| y.key() == x.key()))
+        .cloned()
+        .collect();
+    merged.extend(results.iter().cloned());
+    merged.sort_by(|a, b| a.key().cmp(&b.key()));
+    merged
+}
+
+pub fn save_baseline(path: &Path, results: &[BenchResult]) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(&to_json(results)).unwrap();
+    fs::write(path, data + "\n").map_err(|e| format!("Cannot write {}: {}", path.display(), e))
+}
+
+// Ratio of the new median over the baseline one, if it got slower
+pub fn slowdown(result: &BenchResult, baseline: &[BenchResult]) -> Option<f64> {
+    let old = baseline.iter().find(|x| x.key() == result.key())?;
+    if old.stats.median.is_zero() {
+        return None;
+    }
+    let ratio = result.stats.median.as_secs_f64() / old.stats.median.as_secs_f64();
+    if ratio > SLOWDOWN_RATIO {
+        Some(ratio)
+    } else {
+        None
+    }
+}
+
+pub fn fmt_duration(duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    if secs < 1e-3 {
+        format!("{:.1}µs", secs * 1e6)
+    } else if secs < 1.0 {
+        format!("{:.3}ms", secs * 1e3)
+    } else {
+        format!("{:.3}s", secs)
+    }
+}
+
+pub fn print_table(results: &[BenchResult], baseline: &[BenchResult]) {
+    println!("{:>3}  {:<5}  {:>5}  {:>10}  {:>10}  {:>10}  {:>10}", "Day", "Phase", "Runs", "Min", "Median", "Max", "Baseline");
+    for result in results {
+        let old = baseline.iter()
+            .find(|x| x.key() == result.key())
+            .map(|x| fmt_duration(x.stats.median))
+            .unwrap_or_default();
+        let flag = match slowdown(result, baseline) {
+            Some(ratio) => format!("  SLOWER x{:.2}", ratio),
+            None => String::new(),
+        };
+        println!("{:>3}  {:<5}  {:>5}  {:>10}  {:>10}  {:>10}  {:>10}{}",
+            result.day, result.phase, result.runs,
+            fmt_duration(result.stats.min),
+            fmt_duration(result.stats.median),
+            fmt_duration(result.stats.max),
+            old, flag);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(phase: &str, median: u64) -> BenchResult {
+        result_on(12, "input", phase, median)
+    }
+
+    fn result_on(day: u8, input: &str, phase: &str, median: u64) -> BenchResult {
+        let stats = Stats {
+            min: Duration::from_micros(median / 2),
+            median: Duration::from_micros(median),
+            max: Duration::from_micros(median * 2),
+        };
+        BenchResult { day, phase: phase.to_string(), input: input.to_string(), runs: 5, stats }
+    }
+
+    #[test]
+    fn test_stats() {
+        let samples: Vec<Duration> = [5, 1, 4, 2, 3].iter().map(|x| Duration::from_millis(*x)).collect();
+        let stats = Stats::new(&samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.max, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let results = vec![result("parse", 100), result("part2", 2500)];
+        let parsed = from_json(&to_json(&results)).unwrap();
+        assert_eq!(parsed, results);
+
+        assert!(from_json(&json!({"results": [{"day": 1}]})).is_err());
+    }
+
+    #[test]
+    fn test_slowdown() {
+        let baseline = vec![result("parse", 100), result("part1", 100)];
+        assert_eq!(slowdown(&result("parse", 110), &baseline), None);
+        assert!(slowdown(&result("part1", 150), &baseline).unwrap() > 1.49);
+        assert_eq!(slowdown(&result("part2", 150), &baseline), None);
+        // The sample is not compared against the real input
+        assert_eq!(slowdown(&result_on(12, "sample", "part1", 150), &baseline), None);
+    }
+
+    #[test]
+    fn test_merge() {
+        let baseline = vec![result_on(3, "input", "part1", 100), result_on(12, "input", "part1", 100)];
+        let results = vec![result_on(3, "input", "part1", 50), result_on(3, "sample", "part1", 1)];
+        let merged = merge(&baseline, &results);
+        assert_eq!(merged, vec![
+            result_on(3, "input", "part1", 50),
+            result_on(3, "sample", "part1", 1),
+            result_on(12, "input", "part1", 100),
+        ]);
+    }
+
+    #[test]
+    fn test_fmt_duration() {
+        assert_eq!(fmt_duration(Duration::from_nanos(1500)), "1.5µs");
+        assert_eq!(fmt_duration(Duration::from_micros(2500)), "2.500ms");
+        assert_eq!(fmt_duration(Duration::from_millis(1500)), "1.500s");
+    }
+}
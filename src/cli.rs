@@ -0,0 +1,549 @@
+use std::{fmt, path::PathBuf};
+
+use crate::input::InputSource;
+use crate::log::Level;
+
+pub const USAGE: &str = "\
+Usage: aoc <day> [part] [options]
+       aoc d<day>[p<part>] [options]
+       aoc <from>..=<to> [options]
+       aoc --all [options]     (runs in parallel and prints a summary table)
+       aoc verify [days]
+       aoc watch <day> [--part <part>] [--sample | --input <path>]
+       aoc repl <day> [--sample | --input <path>]
+       aoc fetch <day>         (downloads src/dayNN/input.txt unless it exists)
+       aoc submit <day> <part> (solves the part on input.txt and sends the answer)
+       aoc new <day>           (creates src/dayNN/ and registers it)
+
+Options:
+    --input <path>  Read the puzzle input from <path>
+    --sample        Use sample_input.txt of the selected day
+    -               Read the puzzle input from stdin
+    --bench <runs>  Time parsing and solving over <runs> runs
+    --save-baseline Store the --bench results as the new baseline
+    --format <fmt>  Output format, text (default) or json
+    --param <k=v>   Override a parameter of the day, like --param part1_window=5
+    -v, -vv         Print debug output of the solvers to stderr, -vv for all of it";
+
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub struct Target {
+    pub day: u8,
+    pub part: u8,
+}
+
+impl Target {
+    pub fn new(day: u8, part: u8) -> Self {
+        Target { day, part }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "d{}p{}", self.day, self.part)
+    }
+}
+
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+pub enum CliError {
+    Usage(String),
+    UnknownDay(u8),
+    UnknownPart(u8),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Usage(msg) => write!(f, "{}", msg),
+            CliError::UnknownDay(day) => write!(f, "Unknown day: {}", day),
+            CliError::UnknownPart(part) => write!(f, "Unknown part: {} (expected 1 or 2)", part),
+        }
+    }
+}
+
+fn parse_number(inp: &str) -> Result<u8, CliError> {
+    inp.parse::<u8>()
+        .map_err(|_| CliError::Usage(format!("Cannot parse '{}' as a number", inp)))
+}
+
+fn check_part(part: u8) -> Result<u8, CliError> {
+    if part == 1 || part == 2 {
+        Ok(part)
+    } else {
+        Err(CliError::UnknownPart(part))
+    }
+}
+
+// Accepts "5..=9" and "5..9", but not a range without any day in it
+fn parse_range(inp: &str) -> Result<Option<Vec<u8>>, CliError> {
+    let days: Vec<u8> = if let Some((from, to)) = inp.split_once("..=") {
+        (parse_number(from)?..=parse_number(to)?).collect()
+    } else if let Some((from, to)) = inp.split_once("..") {
+        (parse_number(from)?..parse_number(to)?).collect()
+    } else {
+        return Ok(None);
+    };
+    if days.is_empty() {
+        return Err(CliError::Usage(format!("Range {} has no days in it", inp)));
+    }
+    Ok(Some(days))
+}
+
+// Accepts "d7" and "d7p2"
+fn parse_mode(inp: &str) -> Result<Option<(u8, Option<u8>)>, CliError> {
+    let rest = match inp.strip_prefix('d') {
+        Some(rest) => rest,
+        None => return Ok(None),
+    };
+    match rest.split_once('p') {
+        Some((day, part)) => Ok(Some((parse_number(day)?, Some(check_part(parse_number(part)?)?)))),
+        None => Ok(Some((parse_number(rest)?, None))),
+    }
+}
+
+fn expand(days: &[u8], part: Option<u8>, valid_days: &[u8]) -> Result<Vec<Target>, CliError> {
+    let mut targets = vec![];
+    for day in days {
+        if !valid_days.contains(day) {
+            return Err(CliError::UnknownDay(*day));
+        }
+        match part {
+            Some(part) => targets.push(Target::new(*day, part)),
+            None => {
+                targets.push(Target::new(*day, 1));
+                targets.push(Target::new(*day, 2));
+            }
+        }
+    }
+    Ok(targets)
+}
+
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Format {
+    Text,
+    // One object per day and part, for scripts
+    Json,
+}
+
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+pub struct Options {
+    pub targets: Vec<Target>,
+    pub input: InputSource,
+    pub bench: Option<usize>,
+    pub save_baseline: bool,
+    // --all runs every target on a thread pool and prints a summary table
+    pub all: bool,
+    pub format: Format,
+    // --param overrides, applied to the parameters the day declares
+    pub params: Vec<(String, String)>,
+}
+
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+pub enum Command {
+    Run(Options),
+    // Check the selected days against the stored answers
+    Verify(Vec<u8>),
+    // Rebuild and rerun a single day whenever its sources or input change
+    Watch(Options),
+    // Parse a single day once and take commands from stdin
+    Repl(Options),
+    // Download the puzzle input of a day, which may not be solved yet
+    Fetch(u8),
+    // Solve a part on the default input and post the answer
+    Submit(Target),
+    // Create the module of a new day from a template
+    New(u8),
+}
+
+// Removes -v and -vv from args, they apply to every command
+pub fn take_verbosity(args: &mut Vec<String>) -> Level {
+    let mut level = Level::Quiet;
+    args.retain(|arg| match arg.as_str() {
+        "-v" | "--verbose" => {
+            level = level.max(Level::Debug);
+            false
+        },
+        "-vv" => {
+            level = Level::Trace;
+            false
+        },
+        _ => true,
+    });
+    level
+}
+
+pub fn parse_args(args: &[String], valid_days: &[u8]) -> Result<Command, CliError> {
+    match args.first().map(|x| x.as_str()) {
+        Some("verify") => {
+            let args: Vec<&str> = args[1..].iter().map(|x| x.as_str()).collect();
+            if args.is_empty() {
+                return Ok(Command::Verify(valid_days.to_vec()));
+            }
+            let mut days: Vec<u8> = parse_targets(&args, valid_days)?.iter().map(|x| x.day).collect();
+            days.dedup();
+            Ok(Command::Verify(days))
+        },
+        Some("watch") => Ok(Command::Watch(parse_watch(&args[1..], valid_days)?)),
+        Some("fetch") => Ok(Command::Fetch(parse_any_day(&args[1..], "fetch")?)),
+        Some("new") => Ok(Command::New(parse_any_day(&args[1..], "new")?)),
+        Some("submit") => {
+            let args: Vec<&str> = args[1..].iter().map(|x| x.as_str()).collect();
+            match parse_targets(&args, valid_days)?[..] {
+                [target] => Ok(Command::Submit(target)),
+                _ => Err(CliError::Usage("submit needs a single day and part".to_string())),
+            }
+        },
+        Some("repl") => {
+            let options = parse_options(&args[1..], valid_days)?;
+            check_single_day(&options, "repl")?;
+            Ok(Command::Repl(options))
+        },
+        _ => Ok(Command::Run(parse_options(args, valid_days)?)),
+    }
+}
+
+// A single day of the calendar, solved or not
+fn parse_any_day(args: &[String], command: &str) -> Result<u8, CliError> {
+    match args {
+        [day] => {
+            let day = parse_number(day)?;
+            if !(1..=25).contains(&day) {
+                return Err(CliError::UnknownDay(day));
+            }
+            Ok(day)
+        },
+        _ => Err(CliError::Usage(format!("{} needs a single day", command))),
+    }
+}
+
+fn parse_watch(args: &[String], valid_days: &[u8]) -> Result<Options, CliError> {
+    let mut part = None;
+    let mut rest = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--part" {
+            let value = args.next()
+                .ok_or_else(|| CliError::Usage("--part needs 1 or 2".to_string()))?;
+            part = Some(check_part(parse_number(value)?)?);
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+
+    let mut options = parse_options(&rest, valid_days)?;
+    if let Some(part) = part {
+        options.targets.retain(|x| x.part == part);
+    }
+    if options.targets.is_empty() {
+        return Err(CliError::Usage("No part selected".to_string()));
+    }
+    check_single_day(&options, "watch")?;
+    Ok(options)
+}
+
+// watch and repl work on one day and use stdin for themselves
+fn check_single_day(options: &Options, command: &str) -> Result<(), CliError> {
+    if options.all || options.targets.iter().any(|x| x.day != options.targets[0].day) {
+        return Err(CliError::Usage(format!("{} needs a single day", command)));
+    }
+    if options.input == InputSource::Stdin {
+        return Err(CliError::Usage(format!("{} cannot read the input from stdin", command)));
+    }
+    if options.bench.is_some() || options.format != Format::Text {
+        return Err(CliError::Usage(format!("{} does not support --bench or --format", command)));
+    }
+    Ok(())
+}
+
+fn parse_options(args: &[String], valid_days: &[u8]) -> Result<Options, CliError> {
+    let mut input = InputSource::Default;
+    let mut bench = None;
+    let mut save_baseline = false;
+    let mut format = Format::Text;
+    let mut params = vec![];
+    let mut positional: Vec<&str> = vec![];
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => {
+                let path = args.next()
+                    .ok_or_else(|| CliError::Usage("--input needs a path".to_string()))?;
+                input = InputSource::Path(PathBuf::from(path));
+            },
+            "--sample" => input = InputSource::Sample,
+            "--bench" => {
+                let runs = args.next()
+                    .and_then(|x| x.parse::<usize>().ok())
+                    .filter(|x| *x > 0)
+                    .ok_or_else(|| CliError::Usage("--bench needs a number of runs".to_string()))?;
+                bench = Some(runs);
+            },
+            "--save-baseline" => save_baseline = true,
+            "--format" => {
+                format = match args.next().map(|x| x.as_str()) {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    _ => return Err(CliError::Usage("--format needs text or json".to_string())),
+                };
+            },
+            "--param" => {
+                let (name, value) = args.next()
+                    .and_then(|x| x.split_once('='))
+                    .ok_or_else(|| CliError::Usage("--param needs <name>=<value>".to_string()))?;
+                params.push((name.to_string(), value.to_string()));
+            },
+            "-" => input = InputSource::Stdin,
+            _ => positional.push(arg),
+        }
+    }
+
+    let targets = parse_targets(&positional, valid_days)?;
+    let all = positional == ["--all"];
+
+    // An explicit input or parameters only make sense for a single day
+    let explicit = matches!(input, InputSource::Path(_) | InputSource::Stdin) || !params.is_empty();
+    if explicit && targets.iter().any(|x| x.day != targets[0].day) {
+        return Err(CliError::Usage("--input, - and --param can only be used with a single day".to_string()));
+    }
+
+    if save_baseline && bench.is_none() {
+        return Err(CliError::Usage("--save-baseline needs --bench".to_string()));
+    }
+
+    if format == Format::Json && bench.is_some() {
+        return Err(CliError::Usage("--format json cannot be used with --bench".to_string()));
+    }
+
+    Ok(Options { targets, input, bench, save_baseline, all, format, params })
+}
+
+fn parse_targets(args: &[&str], valid_days: &[u8]) -> Result<Vec<Target>, CliError> {
+    match args[..] {
+        [] => Err(CliError::Usage("No day selected".to_string())),
+        ["--all"] => expand(valid_days, None, valid_days),
+        [first] => {
+            if let Some(days) = parse_range(first)? {
+                return expand(&days, None, valid_days);
+            }
+            if let Some((day, part)) = parse_mode(first)? {
+                return expand(&[day], part, valid_days);
+            }
+            expand(&[parse_number(first)?], None, valid_days)
+        },
+        [day, part] => {
+            let part = check_part(parse_number(part)?)?;
+            if let Some(days) = parse_range(day)? {
+                return expand(&days, Some(part), valid_days);
+            }
+            expand(&[parse_number(day)?], Some(part), valid_days)
+        },
+        _ => Err(CliError::Usage(format!("Unexpected arguments: {}", args.join(" ")))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAYS: [u8; 3] = [1, 2, 3];
+
+    fn args(inp: &str) -> Vec<String> {
+        inp.split_whitespace().map(|x| x.to_string()).collect()
+    }
+
+    fn parse_run(inp: &str) -> Result<Options, CliError> {
+        match parse_args(&args(inp), &DAYS)? {
+            Command::Run(options) => Ok(options),
+            x => panic!("Expected a run command, got {:?}", x),
+        }
+    }
+
+    fn targets(inp: &str) -> Result<Vec<Target>, CliError> {
+        parse_run(inp).map(|x| x.targets)
+    }
+
+    #[test]
+    fn test_day_and_part() {
+        assert_eq!(targets("2 1"), Ok(vec![Target::new(2, 1)]));
+        assert_eq!(targets("d2p1"), Ok(vec![Target::new(2, 1)]));
+        assert_eq!(targets("d3"), Ok(vec![Target::new(3, 1), Target::new(3, 2)]));
+        assert_eq!(targets("3"), Ok(vec![Target::new(3, 1), Target::new(3, 2)]));
+    }
+
+    #[test]
+    fn test_ranges() {
+        let result = targets("1..=2").unwrap();
+        assert_eq!(result, vec![Target::new(1, 1), Target::new(1, 2), Target::new(2, 1), Target::new(2, 2)]);
+
+        let result = targets("1..3 2").unwrap();
+        assert_eq!(result, vec![Target::new(1, 2), Target::new(2, 2)]);
+
+        let options = parse_run("--all").unwrap();
+        assert_eq!(options.targets.len(), 6);
+        assert!(options.all);
+        assert!(!parse_run("1..=3").unwrap().all);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(targets("4"), Err(CliError::UnknownDay(4)));
+        assert_eq!(targets("1..=4"), Err(CliError::UnknownDay(4)));
+        assert_eq!(targets("d1p3"), Err(CliError::UnknownPart(3)));
+        assert!(matches!(targets(""), Err(CliError::Usage(_))));
+        assert!(matches!(targets("foo"), Err(CliError::Usage(_))));
+        assert_eq!(targets("3..=1"), Err(CliError::Usage("Range 3..=1 has no days in it".to_string())));
+        assert!(matches!(targets("2..2"), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn test_input_source() {
+        let options = parse_run("3 1").unwrap();
+        assert_eq!(options.input, InputSource::Default);
+
+        let options = parse_run("--sample 1..=3").unwrap();
+        assert_eq!(options.input, InputSource::Sample);
+        assert_eq!(options.targets.len(), 6);
+
+        let options = parse_run("d2 --input other.txt").unwrap();
+        assert_eq!(options.input, InputSource::Path(PathBuf::from("other.txt")));
+        assert_eq!(options.targets, vec![Target::new(2, 1), Target::new(2, 2)]);
+
+        let options = parse_run("2 1 -").unwrap();
+        assert_eq!(options.input, InputSource::Stdin);
+
+        assert!(matches!(parse_run("2 --input"), Err(CliError::Usage(_))));
+        assert!(matches!(parse_run("--all -"), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn test_bench() {
+        let options = parse_run("2 --bench 10").unwrap();
+        assert_eq!(options.bench, Some(10));
+        assert!(!options.save_baseline);
+
+        let options = parse_run("--all --bench 3 --save-baseline").unwrap();
+        assert_eq!(options.bench, Some(3));
+        assert!(options.save_baseline);
+
+        assert_eq!(parse_run("2").unwrap().bench, None);
+        assert!(matches!(parse_run("2 --bench"), Err(CliError::Usage(_))));
+        assert!(matches!(parse_run("2 --bench 0"), Err(CliError::Usage(_))));
+        assert!(matches!(parse_run("2 --save-baseline"), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(parse_run("2").unwrap().format, Format::Text);
+        assert_eq!(parse_run("2 --format json").unwrap().format, Format::Json);
+        assert_eq!(parse_run("--format text --all").unwrap().format, Format::Text);
+        assert!(matches!(parse_run("2 --format"), Err(CliError::Usage(_))));
+        assert!(matches!(parse_run("2 --format xml"), Err(CliError::Usage(_))));
+        assert!(matches!(parse_run("2 --format json --bench 3"), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn test_params() {
+        let options = parse_run("3 --param part1_window=5 --param part2_window=20").unwrap();
+        assert_eq!(options.params, vec![
+            ("part1_window".to_string(), "5".to_string()),
+            ("part2_window".to_string(), "20".to_string()),
+        ]);
+        assert_eq!(parse_run("2 --param dripper=500,0").unwrap().params[0].1, "500,0");
+        assert!(parse_run("2").unwrap().params.is_empty());
+        assert!(matches!(parse_run("2 --param"), Err(CliError::Usage(_))));
+        assert!(matches!(parse_run("2 --param rounds"), Err(CliError::Usage(_))));
+        assert!(matches!(parse_run("--all --param rounds=3"), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn test_watch() {
+        let watch = |inp: &str| match parse_args(&args(inp), &DAYS) {
+            Ok(Command::Watch(options)) => Ok(options),
+            Ok(x) => panic!("Expected a watch command, got {:?}", x),
+            Err(e) => Err(e),
+        };
+        assert_eq!(watch("watch 3 --part 2").unwrap().targets, vec![Target::new(3, 2)]);
+        assert_eq!(watch("watch 3").unwrap().targets.len(), 2);
+
+        let options = watch("watch d2p1 --sample").unwrap();
+        assert_eq!(options.targets, vec![Target::new(2, 1)]);
+        assert_eq!(options.input, InputSource::Sample);
+
+        assert_eq!(watch("watch 3 --part 3"), Err(CliError::UnknownPart(3)));
+        assert!(matches!(watch("watch 3 1 --part 2"), Err(CliError::Usage(_))));
+        assert!(matches!(watch("watch 1..=2"), Err(CliError::Usage(_))));
+        assert!(matches!(watch("watch --all"), Err(CliError::Usage(_))));
+        assert!(matches!(watch("watch 3 -"), Err(CliError::Usage(_))));
+        assert!(matches!(watch("watch 3 --bench 2"), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn test_repl() {
+        let repl = |inp: &str| match parse_args(&args(inp), &DAYS) {
+            Ok(Command::Repl(options)) => Ok(options),
+            Ok(x) => panic!("Expected a repl command, got {:?}", x),
+            Err(e) => Err(e),
+        };
+        let options = repl("repl 2 --sample").unwrap();
+        assert_eq!(options.targets[0].day, 2);
+        assert_eq!(options.input, InputSource::Sample);
+
+        assert!(matches!(repl("repl 1..=2"), Err(CliError::Usage(_))));
+        assert!(matches!(repl("repl 2 -"), Err(CliError::Usage(_))));
+        assert!(matches!(repl("repl 2 --format json"), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn test_verbosity() {
+        let mut inp = args("3 -v 1");
+        assert_eq!(take_verbosity(&mut inp), Level::Debug);
+        assert_eq!(inp, args("3 1"));
+
+        let mut inp = args("-vv watch 3");
+        assert_eq!(take_verbosity(&mut inp), Level::Trace);
+        assert_eq!(inp, args("watch 3"));
+
+        assert_eq!(take_verbosity(&mut args("3 -")), Level::Quiet);
+    }
+
+    #[test]
+    fn test_fetch() {
+        assert_eq!(parse_args(&args("fetch 2"), &DAYS), Ok(Command::Fetch(2)));
+        // Days without a solver yet can be fetched too
+        assert_eq!(parse_args(&args("fetch 25"), &DAYS), Ok(Command::Fetch(25)));
+        assert_eq!(parse_args(&args("fetch 26"), &DAYS), Err(CliError::UnknownDay(26)));
+        assert!(matches!(parse_args(&args("fetch"), &DAYS), Err(CliError::Usage(_))));
+        assert!(matches!(parse_args(&args("fetch 1 2"), &DAYS), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(parse_args(&args("new 15"), &DAYS), Ok(Command::New(15)));
+        assert_eq!(parse_args(&args("new 0"), &DAYS), Err(CliError::UnknownDay(0)));
+        assert!(matches!(parse_args(&args("new"), &DAYS), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn test_submit() {
+        assert_eq!(parse_args(&args("submit 2 1"), &DAYS), Ok(Command::Submit(Target::new(2, 1))));
+        assert_eq!(parse_args(&args("submit d3p2"), &DAYS), Ok(Command::Submit(Target::new(3, 2))));
+        assert_eq!(parse_args(&args("submit 4 1"), &DAYS), Err(CliError::UnknownDay(4)));
+        assert!(matches!(parse_args(&args("submit 2"), &DAYS), Err(CliError::Usage(_))));
+        assert!(matches!(parse_args(&args("submit 1..=2 1"), &DAYS), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn test_verify() {
+        assert_eq!(parse_args(&args("verify"), &DAYS), Ok(Command::Verify(vec![1, 2, 3])));
+        assert_eq!(parse_args(&args("verify 2"), &DAYS), Ok(Command::Verify(vec![2])));
+        assert_eq!(parse_args(&args("verify 2..=3"), &DAYS), Ok(Command::Verify(vec![2, 3])));
+        assert_eq!(parse_args(&args("verify 4"), &DAYS), Err(CliError::UnknownDay(4)));
+    }
+}
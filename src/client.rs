@@ -0,0 +1,97 @@
+use std::{fmt, time::Duration};
+
+use crate::config::{Config, config_path};
+
+pub const YEAR: u16 = 2022;
+
+const USER_AGENT: &str = "aoc2022 runner (ureq)";
+
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+pub enum ClientError {
+    MissingToken,
+    // The site was reached but rejected the session cookie
+    BadToken,
+    // Inputs and answers of a day only exist from midnight EST on
+    NotUnlocked,
+    NotFound(String),
+    Http(u16, String),
+    Network(String),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientError::MissingToken => write!(f, "No session token, set AOC_SESSION or session in {}", config_path().display()),
+            ClientError::BadToken => write!(f, "The session token was rejected, it may have expired"),
+            ClientError::NotUnlocked => write!(f, "The puzzle is not unlocked yet"),
+            ClientError::NotFound(url) => write!(f, "Not found: {}", url),
+            ClientError::Http(status, body) => write!(f, "HTTP {}: {}", status, body.lines().next().unwrap_or_default()),
+            ClientError::Network(msg) => write!(f, "Cannot reach the server: {}", msg),
+        }
+    }
+}
+
+// Blocking HTTP client for the Advent of Code site, logged in through the
+// session cookie of the config
+pub struct Client {
+    config: Config,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(config: Config) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(30))
+            .build();
+        Client { config, agent }
+    }
+
+    // /2022/day/7/input and the like
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/{}{}", self.config.base_url, YEAR, path)
+    }
+
+    fn cookie(&self) -> Result<String, ClientError> {
+        match &self.config.session {
+            Some(session) => Ok(format!("session={}", session)),
+            None => Err(ClientError::MissingToken),
+        }
+    }
+
+    pub fn get(&self, path: &str) -> Result<String, ClientError> {
+        let url = self.url(path);
+        let response = self.agent.get(&url)
+            .set("Cookie", &self.cookie()?)
+            .call();
+        body(&url, response)
+    }
+
+    pub fn post(&self, path: &str, form: &[(&str, &str)]) -> Result<String, ClientError> {
+        let url = self.url(path);
+        let response = self.agent.post(&url)
+            .set("Cookie", &self.cookie()?)
+            .send_form(form);
+        body(&url, response)
+    }
+}
+
+fn body(url: &str, response: Result<ureq::Response, ureq::Error>) -> Result<String, ClientError> {
+    match response {
+        Ok(response) => response.into_string().map_err(|e| ClientError::Network(e.to_string())),
+        Err(ureq::Error::Status(status, response)) => {
+            let body = response.into_string().unwrap_or_default();
+            // Without a valid session the site asks to log in, as a 400 or a 500
+            if body.contains("log in") {
+                return Err(ClientError::BadToken);
+            }
+            match status {
+                404 if body.contains("before it unlocks") => Err(ClientError::NotUnlocked),
+                404 => Err(ClientError::NotFound(url.to_string())),
+                _ => Err(ClientError::Http(status, body)),
+            }
+        },
+        Err(ureq::Error::Transport(e)) => Err(ClientError::Network(e.to_string())),
+    }
+}
@@ -0,0 +1,84 @@
+use std::{env, fs, path::PathBuf};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+// Settings for talking to the Advent of Code site. They come from aoc.toml
+// in the crate root (not committed, it holds the session token):
+//
+//     session = "53616c7465645f5f..."
+//     base_url = "http://localhost:8080"
+//
+// AOC_SESSION and AOC_BASE_URL override the file, AOC_CONFIG moves it.
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: String,
+}
+
+pub fn config_path() -> PathBuf {
+    match env::var_os("AOC_CONFIG") {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("aoc.toml"),
+    }
+}
+
+fn get_string(table: &toml::Table, key: &str) -> Result<Option<String>, String> {
+    match table.get(key) {
+        None => Ok(None),
+        Some(toml::Value::String(x)) => Ok(Some(x.trim().to_string())),
+        Some(x) => Err(format!("Expected a string for {}, got {}", key, x)),
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config { session: None, base_url: DEFAULT_BASE_URL.to_string() }
+    }
+}
+
+impl Config {
+    pub fn parse(data: &str) -> Result<Self, String> {
+        let table = data.parse::<toml::Table>().map_err(|e| e.to_string())?;
+        let session = get_string(&table, "session")?;
+        let base_url = get_string(&table, "base_url")?.unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+        Ok(Config { session, base_url })
+    }
+
+    // The config file if there is one, then the environment on top
+    pub fn load() -> Result<Self, String> {
+        let path = config_path();
+        let mut config = if path.exists() {
+            let data = fs::read_to_string(&path)
+                .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+            Config::parse(&data).map_err(|e| format!("{}: {}", path.display(), e))?
+        } else {
+            Config::default()
+        };
+        if let Ok(session) = env::var("AOC_SESSION") {
+            config.session = Some(session.trim().to_string());
+        }
+        if let Ok(base_url) = env::var("AOC_BASE_URL") {
+            config.base_url = base_url;
+        }
+        config.session = config.session.filter(|x| !x.is_empty());
+        config.base_url = config.base_url.trim_end_matches('/').to_string();
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse("session = \" abc \"\nbase_url = \"http://localhost:1234\"").unwrap();
+        assert_eq!(config.session, Some("abc".to_string()));
+        assert_eq!(config.base_url, "http://localhost:1234");
+
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+        assert!(Config::parse("session = 12").is_err());
+    }
+}
@@ -1,54 +1,73 @@
-use std::fs;
+use crate::answer::Answer;
+use crate::error::{AocError, Result, parse_at};
+use crate::params::Params;
+use crate::parse::{blocks, lines};
+use crate::solution::Solution;
 
-pub fn part1() {
-    let mut sums: Vec<u32> = vec![];
-    let data: String = fs::read_to_string("./src/day01/input.txt").expect("Cannot read file");
-    //println!("{}", data);
+pub struct Day01;
 
-    let elves:Vec<&str> = data.split("\n\n").collect();
-    let elves:Vec<Vec<&str>> = elves.iter().map(|x| x.split("\n").collect()).collect();
-    
-    for elf in elves {
+pub fn parse_input(data: &str) -> Result<Vec<u32>> {
+    let mut sums: Vec<u32> = vec![];
+    for elf in blocks(data) {
         let mut sum :u32 = 0;
-        for num in elf {
-            let num: u32 = match num.parse() {
-                Err(_e) => 0,
-                Ok(n) => n,
-            };
-            sum += num;          
+        for num in lines(elf) {
+            let num: u32 = parse_at(data, num.trim())?;
+            sum += num;
         }
         sums.push(sum);
     }
-    //println!("{:?}", sums);
-    let maxvalue = sums.iter().max();
-    match maxvalue {
-        Some(max) => println!("ANS: {}", max),
-        None => println!("Empty vector?"),
-    };
+    if sums.is_empty() {
+        return Err(AocError::at(data, &data[data.trim_end().len()..], "Expected at least 1 elf"));
+    }
+    trace!("Calories per elf: {:?}", sums);
+    Ok(sums)
 }
 
-pub fn part2() {
-    let mut sums: Vec<u32> = vec![];
-    let data: String = fs::read_to_string("./src/day01/input.txt").expect("Cannot read file");
-    //println!("{}", data);
+impl Solution for Day01 {
+    type Input = Vec<u32>;
 
-    let elves:Vec<&str> = data.split("\n\n").collect();
-    let elves:Vec<Vec<&str>> = elves.iter().map(|x| x.split("\n").collect()).collect();
-    
-    for elf in elves {
-        let mut sum :u32 = 0;
-        for num in elf {
-            let num: u32 = match num.parse() {
-                Err(_e) => 0,
-                Ok(n) => n,
-            };
-            sum += num;          
+    fn parse(&self, data: &str) -> Result<Self::Input> {
+        parse_input(data)
+    }
+
+    fn part1(&self, sums: &Self::Input) -> Answer {
+        let maxvalue = sums.iter().max().unwrap();
+        Answer::from(*maxvalue)
+    }
+
+    fn part2(&self, sums: &Self::Input) -> Answer {
+        let mut sums = sums.clone();
+        sums.sort();
+        sums.reverse();
+        let sums: u32 = sums[0] + sums[1] + sums[2];
+        Answer::from(sums)
+    }
+
+    // Part 1 works with a single elf, part 2 adds up the top three
+    fn check_params(&self, sums: &Self::Input, part: u8, _params: &Params) -> std::result::Result<(), String> {
+        if part == 2 && sums.len() < 3 {
+            return Err(format!("Expected at least 3 elves to add up, got {}", sums.len()));
         }
-        sums.push(sum);
+        Ok(())
     }
-    
-    sums.sort();
-    sums.reverse();
-    let sums: u32 = sums[0] + sums[1] + sums[2];
-    println!("ANS: {}", sums);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_input("\n\n").unwrap_err();
+        assert_eq!(err.to_string(), "1:1: Expected at least 1 elf");
+        assert!(parse_input("1\nx\n").is_err());
+    }
+
+    #[test]
+    fn test_two_elves() {
+        let sums = parse_input("1\n2\n\n4\n").unwrap();
+        assert_eq!(Day01.part1(&sums), Answer::from(4));
+        assert_eq!(Day01.check_params(&sums, 1, &Day01.params()), Ok(()));
+        assert_eq!(Day01.check_params(&sums, 2, &Day01.params()), Err("Expected at least 3 elves to add up, got 2".to_string()));
+    }
+}
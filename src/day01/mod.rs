@@ -1 +1,3 @@
-pub mod day01;
\ No newline at end of file
+pub mod day01;
+
+pub use self::day01::{Day01, parse_input};
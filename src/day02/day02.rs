@@ -1,4 +1,9 @@
-use std::fs;
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::parse::lines;
+use crate::solution::Solution;
+
+pub struct Day02;
 
 fn get_score(move1: i32, move2: i32) -> i32 {
     // Draw scenario
@@ -19,26 +24,6 @@ fn get_score(move1: i32, move2: i32) -> i32 {
     panic!("This should not be reached!");
 }
 
-#[cfg(test)]
-mod tests {
-    // Note this useful idiom: importing names from outer (for mod tests) scope.
-    use super::*;
-
-    #[test]
-    fn test_get_score_p1() {
-        assert_eq!(get_score(0, 0), 3+1);
-        assert_eq!(get_score(0, 1), 6+2);
-        assert_eq!(get_score(0, 2), 0+3);
-        assert_eq!(get_score(1, 0), 0+1);
-        assert_eq!(get_score(1, 1), 3+2);
-        assert_eq!(get_score(1, 2), 6+3);
-        assert_eq!(get_score(2, 0), 6+1);
-        assert_eq!(get_score(2, 1), 0+2);
-        assert_eq!(get_score(2, 2), 3+3);
-    }
-}
-
-
 fn get_move_int(inp: char) -> i32 {
     match inp {
         'A' => 0,
@@ -51,23 +36,6 @@ fn get_move_int(inp: char) -> i32 {
     }
 }
 
-pub fn part1() {
-    let data: String = fs::read_to_string("./src/day02/input.txt").expect("Cannot read file");
-    let mut score :i32= 0;
-    //println!("{}", data);
-
-    let lines:Vec<&str> = data.trim().split("\n").collect();
-    for line in lines {
-        let moves:Vec<&str>= line.trim().split(" ").collect();
-        let moves:Vec<char> = moves.iter().map(|x| x.chars().nth(0).unwrap()).collect();
-        let move1 = get_move_int(moves[0]);
-        let move2 = get_move_int(moves[1]);
-        score += get_score(move1, move2);
-
-    }
-    println!("ANS: {}", score);
-}
-
 fn get_score_part2(opp_move: i32, outcome: char) -> i32 {
     if opp_move > 2 {
         panic!("Invalid opp_move");
@@ -77,42 +45,89 @@ fn get_score_part2(opp_move: i32, outcome: char) -> i32 {
         'X' => {
             // Lose scenario
             let mymove = (opp_move - 1 + 3) % 3;
-            let score = mymove + 1;
-            score
+            mymove + 1
         },
         'Y' => {
             // Draw scenario
             let mymove = opp_move;
-            let score = mymove + 1 + 3;
-            score
+            mymove + 1 + 3
         },
         'Z' => {
             // Win scenario
             let mymove = (opp_move + 1) % 3;
-            let score = mymove + 1 + 6;
-            score
+            mymove + 1 + 6
         }
         _ => panic!("Invalid outcome")
     }    
 }
 
-pub fn part2() {
-    let data: String = fs::read_to_string("./src/day02/input.txt").expect("Cannot read file");
-    let mut total :i32= 0;
-
-    let lines:Vec<&str> = data.trim().split("\n").collect();
-    for line in lines {
-        let moves:Vec<&str>= line.trim().split(" ").collect();
-        let moves:Vec<char> = moves.iter().map(|x| x.chars().nth(0).unwrap()).collect();
-        let opp_move = get_move_int(moves[0]);
-        let outcome = moves[1];
-        let score = get_score_part2(opp_move, outcome);
-        println!("{}", score);
-        if score == 0 {
-            break;
+fn parse_move(data: &str, inp: &str, valid: &str) -> Result<char> {
+    let mut chars = inp.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if valid.contains(c) => Ok(c),
+        _ => Err(AocError::at(data, inp, format!("Unexpected move '{}', expected one of {}", inp, valid))),
+    }
+}
+
+pub fn parse_input(data: &str) -> Result<Vec<(char, char)>> {
+    lines(data).iter()
+        .map(|line| {
+            let (opp, mine) = line.trim().split_once(' ')
+                .ok_or_else(|| AocError::at(data, line, "Expected two moves separated by a space"))?;
+            Ok((parse_move(data, opp, "ABC")?, parse_move(data, mine, "XYZ")?))
+        })
+        .collect()
+}
+
+impl Solution for Day02 {
+    type Input = Vec<(char, char)>;
+
+    fn parse(&self, data: &str) -> Result<Self::Input> {
+        parse_input(data)
+    }
+
+    fn part1(&self, rounds: &Self::Input) -> Answer {
+        let mut score :i32= 0;
+        for (opp, mine) in rounds {
+            let move1 = get_move_int(*opp);
+            let move2 = get_move_int(*mine);
+            score += get_score(move1, move2);
+        }
+        Answer::from(score)
+    }
+
+    fn part2(&self, rounds: &Self::Input) -> Answer {
+        let mut total :i32= 0;
+        for (opp, outcome) in rounds {
+            let opp_move = get_move_int(*opp);
+            let score = get_score_part2(opp_move, *outcome);
+            trace!("Score: {}", score);
+            if score == 0 {
+                break;
+            }
+
+            total += score;
         }
+        Answer::from(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
 
-        total += score;
+    #[test]
+    #[allow(clippy::identity_op)]
+    fn test_get_score_p1() {
+        assert_eq!(get_score(0, 0), 3+1);
+        assert_eq!(get_score(0, 1), 6+2);
+        assert_eq!(get_score(0, 2), 0+3);
+        assert_eq!(get_score(1, 0), 0+1);
+        assert_eq!(get_score(1, 1), 3+2);
+        assert_eq!(get_score(1, 2), 6+3);
+        assert_eq!(get_score(2, 0), 6+1);
+        assert_eq!(get_score(2, 1), 0+2);
+        assert_eq!(get_score(2, 2), 3+3);
     }
-    println!("ANS: {}", total);
-}
\ No newline at end of file
+}
@@ -1 +1,3 @@
-pub mod day02;
\ No newline at end of file
+pub mod day02;
+
+pub use self::day02::{Day02, parse_input};
@@ -1,66 +1,32 @@
-use std::fs;
 use std::collections::HashSet;
 
-#[cfg(test)]
-mod tests {
-    // Note this useful idiom: importing names from outer (for mod tests) scope.
-    use super::*;
-
-    #[test]
-    fn test_get_intersection() {
-        let str1 = "vJrwpWtwJgWrhcsFMMfFFhFp".to_string();
-        let str2 = "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL".to_string();
-        let str3 = "PmmdzqPrVvPwwTWBwg".to_string();
-        
-        let str1:HashSet<char> = HashSet::from_iter(str1.chars());
-        let str2:HashSet<char> = HashSet::from_iter(str2.chars());
-        let str3:HashSet<char> = HashSet::from_iter(str3.chars());
-
-        let group:Vec<HashSet<char>> = Vec::from([str1, str2, str3]);
-
-        let result = get_intersection_three(group);
-        assert_eq!(result, vec!['r']);
-    }
-}
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::parse::lines;
+use crate::solution::Solution;
 
+pub struct Day03;
 
 fn get_score(a:char) -> u32{
     let ascii  = a as u32;
 
-    if ascii >= 97 && ascii <= 122 {
+    if (97..=122).contains(&ascii) {
         ascii - 96
-    } else if ascii >= 65 && ascii <= 90 {
+    } else if (65..=90).contains(&ascii) {
         ascii - 38
     } else {
         panic!("Also unreachable!");
     }
 }
 
-fn get_intersection(comp1: &str, comp2: &str) -> char {
+fn get_intersection(comp1: &str, comp2: &str) -> Option<char> {
     let comp1:Vec<char> = comp1.chars().collect();
     let comp2:Vec<char> = comp2.chars().collect();
 
     let comp1:HashSet<char> = HashSet::from_iter(comp1);
     let comp2:HashSet<char> = HashSet::from_iter(comp2);
 
-    for i in comp1.intersection(&comp2) {
-        return *i;
-    }
-    panic!("Unreachable!");
-}
-
-pub fn part1() {
-    let data: String = fs::read_to_string("./src/day03/input.txt").expect("Cannot read file");
-    let mut score = 0;
-    let lines:Vec<&str> = data.trim().split("\n").collect();
-    for line in lines {
-        let line = line.trim();
-        let len = line.len() / 2;
-        
-        let result = get_intersection(&line[..len], &line[len..]);
-        score += get_score(result);
-    }
-    println!("ANS: {}", score);
+    comp1.intersection(&comp2).next().copied()
 }
 
 fn get_intersection_three(group:Vec<HashSet<char>>) -> Vec<char>{
@@ -68,44 +34,127 @@ fn get_intersection_three(group:Vec<HashSet<char>>) -> Vec<char>{
         panic!("Invalid length group received");
     }
     
-    let hash1 = group.get(0).unwrap();
+    let hash1 = group.first().unwrap();
     let hash2 = group.get(1).unwrap();
     let hash3 = group.get(2).unwrap();
 
     //let foo= Vec::from_iter(hash1.intersection(hash2));
-    let result:HashSet<char> = HashSet::from_iter(hash1.intersection(hash2).into_iter().cloned());
+    let result:HashSet<char> = HashSet::from_iter(hash1.intersection(hash2).cloned());
     //let result = HashSet::from_iter(result.into_iter().cloned());
-    let result = Vec::from_iter(result.intersection(hash3).into_iter().cloned());
+    let result = Vec::from_iter(result.intersection(hash3).cloned());
 
     result
     
     //let result = Vec::from_iter::<char>(hash3.intersection(&temp).collect());
 }
 
-pub fn part2() {
-    let data: String = fs::read_to_string("./src/day03/input.txt").expect("Cannot read file");
+pub fn parse_input(data: &str) -> Result<Vec<String>> {
+    let mut rucksacks = vec![];
+    let lines: Vec<&str> = lines(data).into_iter().map(|x| x.trim()).collect();
+    for line in &lines {
+        if let Some((i, c)) = line.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+            return Err(AocError::at(data, &line[i..], format!("Unexpected item '{}'", c)));
+        }
+        // Both compartments hold the same number of items
+        if !line.len().is_multiple_of(2) {
+            return Err(AocError::at(data, line, format!("Rucksack of {} items cannot be split into two compartments", line.len())));
+        }
+        let len = line.len() / 2;
+        if get_intersection(&line[..len], &line[len..]).is_none() {
+            return Err(AocError::at(data, line, "Compartments share no item"));
+        }
+        rucksacks.push(line.to_string());
+    }
+    if !lines.len().is_multiple_of(3) {
+        return Err(AocError::at(data, &data[data.trim_end().len()..],
+            format!("Expected groups of 3 rucksacks, the last group has {}", lines.len() % 3)));
+    }
+    for group in lines.chunks(3) {
+        let items: Vec<HashSet<char>> = group.iter().map(|x| x.chars().collect()).collect();
+        if get_intersection_three(items).is_empty() {
+            return Err(AocError::at(data, group[0], "Rucksacks of this group share no item"));
+        }
+    }
+    Ok(rucksacks)
+}
+
+impl Solution for Day03 {
+    type Input = Vec<String>;
 
-    let lines:Vec<&str> = data.trim().split("\n").collect();
-    let mut hashy:HashSet<char>;
-    let mut group: Vec<HashSet<char>> = vec![];
+    fn parse(&self, data: &str) -> Result<Self::Input> {
+        parse_input(data)
+    }
 
-    let mut group_counter = 0;
-    let mut score = 0;
-    for line in lines {
-        hashy = HashSet::from_iter(line.chars());
-        if group_counter < 3 {
-            group.push(hashy);
+    fn part1(&self, lines: &Self::Input) -> Answer {
+        let mut score = 0;
+        for line in lines {
+            let len = line.len() / 2;
+            
+            // parse_input made sure the compartments share an item
+            let result = get_intersection(&line[..len], &line[len..]).unwrap();
+            score += get_score(result);
         }
-        group_counter += 1;
-        if group_counter == 3 {
-            group_counter = 0;
-            let result = get_intersection_three(group);
-            let result = result.get(0).unwrap();
-            score += get_score(*result);
-            group = vec![];
+        Answer::from(score)
+    }
+
+    fn part2(&self, lines: &Self::Input) -> Answer {
+        let mut hashy:HashSet<char>;
+        let mut group: Vec<HashSet<char>> = vec![];
+
+        let mut group_counter = 0;
+        let mut score = 0;
+        for line in lines {
+            hashy = HashSet::from_iter(line.chars());
+            if group_counter < 3 {
+                group.push(hashy);
+            }
+            group_counter += 1;
+            if group_counter == 3 {
+                group_counter = 0;
+                let result = get_intersection_three(group);
+                let result = result.first().unwrap();
+                score += get_score(*result);
+                group = vec![];
+            }
+            
         }
+        Answer::from(score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_get_intersection() {
+        let str1 = "vJrwpWtwJgWrhcsFMMfFFhFp".to_string();
+        let str2 = "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL".to_string();
+        let str3 = "PmmdzqPrVvPwwTWBwg".to_string();
         
+        let str1:HashSet<char> = HashSet::from_iter(str1.chars());
+        let str2:HashSet<char> = HashSet::from_iter(str2.chars());
+        let str3:HashSet<char> = HashSet::from_iter(str3.chars());
+
+        let group:Vec<HashSet<char>> = Vec::from([str1, str2, str3]);
+
+        let result = get_intersection_three(group);
+        assert_eq!(result, vec!['r']);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_input("abca\nabc\n").unwrap_err();
+        assert_eq!(err.to_string(), "2:1: Rucksack of 3 items cannot be split into two compartments");
+
+        let err = parse_input("abca\nab\n").unwrap_err();
+        assert_eq!(err.to_string(), "2:1: Compartments share no item");
+
+        let err = parse_input("aa\nbb\n").unwrap_err();
+        assert_eq!(err.to_string(), "2:3: Expected groups of 3 rucksacks, the last group has 2");
+
+        let err = parse_input("aa\naa\naa\naa\nbb\nbb\n").unwrap_err();
+        assert_eq!(err.to_string(), "4:1: Rucksacks of this group share no item");
     }
-    println!("ANS: {}", score);
-  
-}
\ No newline at end of file
+}
@@ -1 +1,3 @@
-pub mod day03;
\ No newline at end of file
+pub mod day03;
+
+pub use self::day03::{Day03, parse_input};
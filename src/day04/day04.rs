@@ -1,131 +1,106 @@
-use std::fs;
+use crate::answer::Answer;
+use crate::error::{AocError, Result, parse_at};
+use crate::interval::Interval;
+use crate::parse::lines;
+use crate::solution::Solution;
 
-#[cfg(test)]
-mod tests {
-    // Note this useful idiom: importing names from outer (for mod tests) scope.
-    use super::*;
-
-    #[test]
-    fn test_get_intersection() {
-        let foo: &str = &"12-34"[..];
-        let a:Vec<i32> = foo.split("-").map(|x| x.parse::<i32>().unwrap()).collect();
-        println!("{:?}", a);
-    }
+pub struct Day04;
 
-    #[test]
-    fn test_part1() {
-        assert_eq!(is_within_range(&Entry{start: 2, end: 4}, &Entry{start:6, end: 8}), false);
-        assert_eq!(is_within_range(&Entry{start: 2, end: 3}, &Entry{start:4, end: 5}), false);
-        assert_eq!(is_within_range(&Entry{start: 5, end: 7}, &Entry{start:7, end: 9}), false);
-        assert_eq!(is_within_range(&Entry{start: 2, end: 8}, &Entry{start:3, end: 7}), true);
-        assert_eq!(is_within_range(&Entry{start: 6, end: 6}, &Entry{start:4, end: 6}), true);
-        assert_eq!(is_within_range(&Entry{start: 2, end: 6}, &Entry{start:4, end: 8}), false);
-    }
+// Whether one of the two contains the other
+pub fn is_within_range(entry1: &Interval<i32>, entry2: &Interval<i32>) -> bool {
+    entry1.covers(*entry2) || entry2.covers(*entry1)
+}
 
-    #[test]
-    fn test_part2() {
-        assert_eq!(is_overlapping(Entry{start: 2, end: 4}, Entry{start:6, end: 8}), false);
-        assert_eq!(is_overlapping(Entry{start: 2, end: 3}, Entry{start:4, end: 5}), false);
-        assert_eq!(is_overlapping(Entry{start: 5, end: 7}, Entry{start:7, end: 9}), true);
-        assert_eq!(is_overlapping(Entry{start: 2, end: 8}, Entry{start:3, end: 7}), true);
-        assert_eq!(is_overlapping(Entry{start: 6, end: 6}, Entry{start:4, end: 6}), true);
-        assert_eq!(is_overlapping(Entry{start: 2, end: 6}, Entry{start:4, end: 8}), true);
+// Parses "2-4"
+fn parse_entry(data: &str, inp: &str) -> Result<Interval<i32>> {
+    let (start, end) = inp.split_once('-')
+        .ok_or_else(|| AocError::at(data, inp, format!("Expected a range like 2-4, got '{}'", inp)))?;
+    let start: i32 = parse_at(data, start)?;
+    let end: i32 = parse_at(data, end)?;
+    if start > end {
+        return Err(AocError::at(data, inp, format!("Range {} ends before it starts", inp)));
     }
+    Ok(Interval::new(start, end))
 }
 
-
-#[derive(Debug)]
-struct Entry {
-    start: i32,
-    end: i32,
+pub fn parse_input(data: &str) -> Result<Vec<(Interval<i32>, Interval<i32>)>> {
+    let mut pairs = vec![];
+    for line in lines(data) {
+        let (entry1, entry2) = line.trim().split_once(',')
+            .ok_or_else(|| AocError::at(data, line, "Expected two ranges separated by ','"))?;
+        pairs.push((parse_entry(data, entry1)?, parse_entry(data, entry2)?));
+    }
+    Ok(pairs)
 }
 
-fn is_overlapping(entry1: Entry, entry2: Entry) -> bool {
-    /*if is_within_range(&entry1, &entry2) {
-        return true;
-    }*/
+impl Solution for Day04 {
+    type Input = Vec<(Interval<i32>, Interval<i32>)>;
 
-    // Check if [2,6] [4,8] that 4 is present within [2,6]
-    if entry2.start >= entry1.start && entry2.start <= entry1.end {
-        return true;
+    fn parse(&self, data: &str) -> Result<Self::Input> {
+        parse_input(data)
     }
 
-    // Swap and check the above 
-    if entry1.start >= entry2.start && entry1.start <= entry2.end {
-        return true;
-    }
-    false
-}
-
-fn is_within_range(entry1: &Entry, entry2: &Entry) -> bool {
-    // Check if entry1 is big enough to contain entry2
-    if entry1.start <= entry2.start && entry1.end >= entry2.end {
-        return true;
+    fn part1(&self, pairs: &Self::Input) -> Answer {
+        let mut score = 0;
+        for (entry1, entry2) in pairs {
+            let within = is_within_range(entry1, entry2);
+            trace!("Checking {:?} and {:?}: {}", entry1, entry2, within);
+            if within {
+                score += 1;
+            }
+        }
+        Answer::from(score)
     }
 
-    // Check if entry2 is big enough to contain entry1
-    if entry2.start <= entry1.start && entry2.end >= entry1.end {
-        return true;
+    fn part2(&self, pairs: &Self::Input) -> Answer {
+        let mut score = 0;
+        for (entry1, entry2) in pairs {
+            if entry1.overlaps(*entry2) {
+                score += 1;
+            }
+        }
+        Answer::from(score)
     }
-
-    false
 }
 
-pub fn part1() {
-    let data: String = fs::read_to_string("./src/day04/input.txt").expect("Cannot read file");
-    let mut score = 0;
-    let lines:Vec<&str> = data.trim().split("\n").collect();
-    for line in lines {
-        //let parsed:Vec<Vec<i32>> = 
-        let parsed:Vec<Vec<i32>> = line.split(",")
-                .map(|x| x.split("-").map(|y| y.parse::<i32>().unwrap()).collect())
-                .collect();
-
-        let entry1 = Entry{
-            start: parsed[0][0],
-            end: parsed[0][1],
-        };
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
 
-        let entry2 = Entry{
-            start: parsed[1][0],
-            end: parsed[1][1],
-        };
-        //println!("Checking {:?} and {:?}", entry1, entry2);
-        if is_within_range(&entry1, &entry2) {
-            //println!("Yah!");
-            score += 1;
-        } else {
-            //println!("Nah!");
-        }
-        
+    #[test]
+    fn test_get_intersection() {
+        let entry: &str = "12-34";
+        let a:Vec<i32> = entry.split("-").map(|x| x.parse::<i32>().unwrap()).collect();
+        assert_eq!(a, vec![12, 34]);
     }
-    println!("ANS: {}", score);
-}
 
-pub fn part2() {
-    let data: String = fs::read_to_string("./src/day04/input.txt").expect("Cannot read file");
-    let mut score = 0;
-    let lines:Vec<&str> = data.trim().split("\n").collect();
-    for line in lines {
-        //let parsed:Vec<Vec<i32>> = 
-        let parsed:Vec<Vec<i32>> = line.split(",")
-                .map(|x| x.split("-").map(|y| y.parse::<i32>().unwrap()).collect())
-                .collect();
+    #[test]
+    fn test_part1() {
+        assert_eq!(is_within_range(&Interval::new(2, 4), &Interval::new(6, 8)), false);
+        assert_eq!(is_within_range(&Interval::new(2, 3), &Interval::new(4, 5)), false);
+        assert_eq!(is_within_range(&Interval::new(5, 7), &Interval::new(7, 9)), false);
+        assert_eq!(is_within_range(&Interval::new(2, 8), &Interval::new(3, 7)), true);
+        assert_eq!(is_within_range(&Interval::new(6, 6), &Interval::new(4, 6)), true);
+        assert_eq!(is_within_range(&Interval::new(2, 6), &Interval::new(4, 8)), false);
+    }
 
-        let entry1 = Entry{
-            start: parsed[0][0],
-            end: parsed[0][1],
-        };
+    #[test]
+    fn test_part2() {
+        assert_eq!(Interval::new(2, 4).overlaps(Interval::new(6, 8)), false);
+        assert_eq!(Interval::new(2, 3).overlaps(Interval::new(4, 5)), false);
+        assert_eq!(Interval::new(5, 7).overlaps(Interval::new(7, 9)), true);
+        assert_eq!(Interval::new(2, 8).overlaps(Interval::new(3, 7)), true);
+        assert_eq!(Interval::new(6, 6).overlaps(Interval::new(4, 6)), true);
+        assert_eq!(Interval::new(2, 6).overlaps(Interval::new(4, 8)), true);
+    }
 
-        let entry2 = Entry{
-            start: parsed[1][0],
-            end: parsed[1][1],
-        };
-        
-        if is_overlapping(entry1, entry2) {
-            score += 1;
-        }
-        
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_input("2-4,6-8
+5-3,1-2
+").unwrap_err();
+        assert_eq!(err.to_string(), "2:1: Range 5-3 ends before it starts");
     }
-    println!("ANS: {}", score);
-}
\ No newline at end of file
+}
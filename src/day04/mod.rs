@@ -1 +1,3 @@
-pub mod day04;
\ No newline at end of file
+pub mod day04;
+
+pub use self::day04::{Day04, is_within_range, parse_input};
@@ -1,118 +1,172 @@
-use std::fs;
 use regex::Regex;
 
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::parse::{captures, lines};
+use crate::solution::Solution;
+
+pub struct Day05;
+
 #[cfg(test)]
 mod tests {
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
+    use crate::input::InputSource;
 
     #[test]
     fn test_part1_parser() {
-        let (stacks, ops) = parse_input("./src/day05/sample_input.txt");
+        let (stacks, ops) = parse_input(&InputSource::Sample.read(5).unwrap()).unwrap();
         assert_eq!(stacks[0], vec!['Z', 'N']);
         assert_eq!(stacks[1], vec!['M', 'C', 'D']);
         assert_eq!(stacks[2], vec!['P']);
 
-        assert_eq!(ops[0], StackOp{num: 1, from: 2, to: 1});
-        assert_eq!(ops[1], StackOp{num: 3, from: 1, to: 3});
-        assert_eq!(ops[2], StackOp{num: 2, from: 2, to: 1});
-        assert_eq!(ops[3], StackOp{num: 1, from: 1, to: 2});
+        // Stack indices are stored zero-based
+        assert_eq!(ops[0], StackOp{num: 1, from: 1, to: 0});
+        assert_eq!(ops[1], StackOp{num: 3, from: 0, to: 2});
+        assert_eq!(ops[2], StackOp{num: 2, from: 1, to: 0});
+        assert_eq!(ops[3], StackOp{num: 1, from: 0, to: 1});
     }
 
     #[test]
     fn test_part2() {
-        
+        let input = parse_input(&InputSource::Sample.read(5).unwrap()).unwrap();
+        assert_eq!(Day05.part2(&input), Answer::from("MCD"));
+    }
+
+    #[test]
+    fn test_impossible_moves() {
+        let data = InputSource::Sample.read(5).unwrap();
+        let err = parse_input(&data.replace("move 3 from 1 to 3", "move 4 from 1 to 3")).unwrap_err();
+        assert_eq!(err.to_string(), "7:6: Cannot move 4 crates from stack 1 holding 3");
+
+        let err = parse_input(&data.replace("move 1 from 1 to 2", "move 1 from 1 to 3")).unwrap_err();
+        assert_eq!(err.to_string(), "8:1: Stack 2 ends up empty, it has no crate on top");
+    }
+
+    #[test]
+    fn test_crlf() {
+        let data = InputSource::Sample.read(5).unwrap();
+        let crlf = data.replace('\n', "\r\n");
+        assert_eq!(parse_input(&crlf).unwrap(), parse_input(&data).unwrap());
     }
 }
 #[derive(PartialEq,Eq)]
 #[derive(Debug)]
-struct StackOp {
+pub struct StackOp {
     num: i32,
     from: usize,
     to: usize,
 }
 
-fn parse_input(filepath: &str) -> (Vec<Vec<char>>, Vec<StackOp>) {
-    let data: String = fs::read_to_string(filepath).expect("Cannot read file");
-    let lines:Vec<&str> = data.split("\n").collect();
-    let mut line_n = 0;
-
-    let mut stacks:Vec<Vec<char>> = vec![];
+pub fn parse_input(data: &str) -> Result<(Vec<Vec<char>>, Vec<StackOp>)> {
+    let lines = lines(data);
     let mut ops:Vec<StackOp> = vec![];
 
-    // Determine number of stacks
-    let stacks_n = (lines[0].len() + 1)/ 4;
-    for _ in 0..stacks_n {
-        stacks.push(vec![]);
-    }
+    let numbering = lines.iter().position(|x| x.trim().starts_with('1'))
+        .ok_or_else(|| AocError::at(data, &data[data.len()..], "Missing the line numbering the stacks"))?;
+    debug!("Stacks end at line {}", numbering + 1);
 
-    for line in &lines {
-        line_n += 1;
-        let mut pos = 0;
-        if line.trim().chars().nth(0) == Some('1') {
-            println!("Parsing done!");
-            break;
-        }
-        for c in line.chars() {
+    // One stack per number. Lines come without trailing spaces, so the
+    // drawing itself does not tell how many there are.
+    let stacks_n = lines[numbering].split_whitespace().count();
+    let mut stacks:Vec<Vec<char>> = vec![vec![]; stacks_n];
+
+    for line in &lines[..numbering] {
+        for (pos, c) in line.char_indices() {
             if c == ' ' || c == '[' || c == ']' {
-                pos += 1;
                 continue;
             }
-            stacks[(pos - 1) / 4].push(c);
-            pos += 1;
+            // Crates sit at columns 1, 5, 9, ...
+            if pos % 4 != 1 || pos / 4 >= stacks.len() {
+                return Err(AocError::at(data, &line[pos..], format!("Unexpected crate '{}' outside of the stacks", c)));
+            }
+            stacks[pos / 4].push(c);
         }
     }
-
     for stack in &mut stacks {
         stack.reverse();
     }
 
+    // Heights of the stacks while the moves are read, along with what
+    // left each stack empty: its number, or the move which took its last
+    // crate. Every stack needs a crate on top at the end.
+    let mut heights: Vec<usize> = stacks.iter().map(|x| x.len()).collect();
+    let mut emptied_by: Vec<&str> = lines[numbering].split_whitespace().collect();
+
     // Parse the StackOps now
     let re = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
-    for line in &lines[line_n+1..] {
-        if *line == "" {
-            break;
+    for line in lines.iter().skip(numbering + 1) {
+        if line.is_empty() {
+            continue;
         }
 
-        let caps = re.captures(line).unwrap();
+        let caps = captures(data, &re, line, "move <n> from <stack> to <stack>")?;
+        let stack = |i: usize| -> Result<usize> {
+            let num: usize = caps.get(i)?;
+            if num == 0 || num > stacks.len() {
+                return Err(AocError::at(data, caps.text(i), format!("Unknown stack {}", num)));
+            }
+            Ok(num - 1)
+        };
         let op = StackOp {
-            num: caps.get(1).unwrap().as_str().parse().unwrap(),
-            from: caps.get(2).unwrap().as_str().parse::<usize>().unwrap() - 1,
-            to: caps.get(3).unwrap().as_str().parse::<usize>().unwrap() - 1,
+            num: caps.get(1)?,
+            from: stack(2)?,
+            to: stack(3)?,
         };
+        let num = op.num as usize;
+        if num > heights[op.from] {
+            return Err(AocError::at(data, caps.text(1), format!("Cannot move {} crates from stack {} holding {}", num, op.from + 1, heights[op.from])));
+        }
+        heights[op.from] -= num;
+        heights[op.to] += num;
+        if heights[op.from] == 0 {
+            emptied_by[op.from] = line;
+        }
         ops.push(op);
     }
+    if let Some(i) = heights.iter().position(|x| *x == 0) {
+        return Err(AocError::at(data, emptied_by[i], format!("Stack {} ends up empty, it has no crate on top", i + 1)));
+    }
     
-    (stacks, ops)
+    Ok((stacks, ops))
 
 }
 
-pub fn part1() {
-    let (mut stacks, ops) = parse_input("./src/day05/input.txt");
-    for op in ops {
-        for _ in 0..op.num {
-            let popped = stacks[op.from].pop().unwrap();
-            stacks[op.to].push(popped);
+impl Solution for Day05 {
+    type Input = (Vec<Vec<char>>, Vec<StackOp>);
+
+    fn parse(&self, data: &str) -> Result<Self::Input> {
+        parse_input(data)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Answer {
+        let (stacks, ops) = input;
+        let mut stacks = stacks.clone();
+        for op in ops {
+            for _ in 0..op.num {
+                let popped = stacks[op.from].pop().unwrap();
+                stacks[op.to].push(popped);
+            }
         }
+        let result:String = stacks.iter().map(|x| x.last().unwrap()).collect();
+        Answer::from(result)
     }
-    let result:String = stacks.iter().map(|x| x.last().unwrap()).collect();
-    println!("ANS: {}", result);
-}
 
-pub fn part2() {
-    let (mut stacks, ops) = parse_input("./src/day05/input.txt");
-    for op in ops {
-        // Popping as group
-        let from_len = stacks[op.from].len();
-        let num_remove = op.num as usize;
-        //println!("from_len: {}\tnum_remove: {}", from_len, num_remove);
-        let sliced:Vec<char> = Vec::from(&stacks[op.from][from_len-num_remove..from_len]);
-        //println!("Sliced: {:?}",sliced);
-        stacks[op.from].drain(from_len-num_remove..from_len);
-
-        //Pushing the result
-        stacks[op.to].extend(sliced);
+    fn part2(&self, input: &Self::Input) -> Answer {
+        let (stacks, ops) = input;
+        let mut stacks = stacks.clone();
+        for op in ops {
+            // Popping as group
+            let from_len = stacks[op.from].len();
+            let num_remove = op.num as usize;
+            let sliced:Vec<char> = Vec::from(&stacks[op.from][from_len-num_remove..from_len]);
+            trace!("Moving {:?} from {} to {}", sliced, op.from, op.to);
+            stacks[op.from].drain(from_len-num_remove..from_len);
+
+            //Pushing the result
+            stacks[op.to].extend(sliced);
+        }
+        let result:String = stacks.iter().map(|x| x.last().unwrap()).collect();
+        Answer::from(result)
     }
-    let result:String = stacks.iter().map(|x| x.last().unwrap()).collect();
-    println!("ANS: {}", result);
-}
\ No newline at end of file
+}
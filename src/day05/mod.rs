@@ -1 +1,3 @@
-pub mod day05;
\ No newline at end of file
+pub mod day05;
+
+pub use self::day05::{Day05, StackOp, parse_input};
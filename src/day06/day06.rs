@@ -1,4 +1,92 @@
-use std::{fs, collections::HashSet};
+use std::collections::HashSet;
+
+use crate::answer::Answer;
+use crate::error::Result;
+use crate::params::Params;
+use crate::repl::parse_arg;
+use crate::solution::{Explored, Solution};
+
+pub struct Day06;
+
+pub fn parse_input(data: &str) -> Vec<char> {
+    data.trim_end().chars().collect()
+}
+
+// End of the first window of winsize distinct characters, if any
+pub fn solve_challenge(data: &[char], winsize: usize) -> Option<usize> {
+    let mut i = 0;
+
+    while i + winsize <= data.len() {
+        let window = &data[i..i+winsize];
+        let uniq:HashSet<char> = HashSet::from_iter(window.iter().cloned());
+        if window.len() == uniq.len() {
+            return Some(i + winsize);
+        }
+        i+=1;
+    }
+    None
+}
+
+fn find_marker(data: &[char], winsize: usize) -> std::result::Result<usize, String> {
+    solve_challenge(data, winsize)
+        .ok_or_else(|| format!("No marker of {} distinct characters in the datastream", winsize))
+}
+
+impl Solution for Day06 {
+    type Input = Vec<char>;
+
+    fn parse(&self, data: &str) -> Result<Self::Input> {
+        Ok(parse_input(data))
+    }
+
+    fn part1(&self, data: &Self::Input) -> Answer {
+        self.part1_with(data, &self.params())
+    }
+
+    fn part2(&self, data: &Self::Input) -> Answer {
+        self.part2_with(data, &self.params())
+    }
+
+    fn params(&self) -> Params {
+        Params::new()
+            .declare("part1_window", 4, 1..=i64::MAX, "Distinct characters of a start-of-packet marker")
+            .declare("part2_window", 14, 1..=i64::MAX, "Distinct characters of a start-of-message marker")
+    }
+
+    fn part1_with(&self, data: &Self::Input, params: &Params) -> Answer {
+        trace!("Datastream of {} characters", data.len());
+        // check_params made sure there is one
+        let result = solve_challenge(data, params.int("part1_window")).unwrap();
+
+        Answer::from(result)
+    }
+
+    fn part2_with(&self, data: &Self::Input, params: &Params) -> Answer {
+        let result = solve_challenge(data, params.int("part2_window")).unwrap();
+        Answer::from(result)
+    }
+
+    fn check_params(&self, data: &Self::Input, part: u8, params: &Params) -> std::result::Result<(), String> {
+        let name = if part == 1 { "part1_window" } else { "part2_window" };
+        find_marker(data, params.int(name)).map(|_| ())
+    }
+
+    fn explore(&self, data: &Self::Input, command: &str, args: &[&str]) -> Option<Explored> {
+        match command {
+            "window" => Some(parse_arg::<usize>(args, 0, "size").and_then(|size| {
+                if size == 0 {
+                    return Err("Window size must be at least 1".to_string());
+                }
+                find_marker(data, size).map(|x| x.to_string())
+            })),
+            _ => None,
+        }
+    }
+
+    fn explore_help(&self) -> &'static [(&'static str, &'static str)] {
+        &[("window <size>", "End of the first marker of <size> distinct characters")]
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -12,10 +100,10 @@ mod tests {
         let input3 :Vec<char> = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".chars().collect();
         let input4 :Vec<char> = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".chars().collect();
 
-        assert_eq!(solve_challenge(input1, 4), 5);
-        assert_eq!(solve_challenge(input2, 4), 6);
-        assert_eq!(solve_challenge(input3, 4), 10);
-        assert_eq!(solve_challenge(input4, 4), 11);
+        assert_eq!(solve_challenge(&input1, 4), Some(5));
+        assert_eq!(solve_challenge(&input2, 4), Some(6));
+        assert_eq!(solve_challenge(&input3, 4), Some(10));
+        assert_eq!(solve_challenge(&input4, 4), Some(11));
     }
 
     #[test]
@@ -26,43 +114,25 @@ mod tests {
         let input4 :Vec<char> = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".chars().collect();
         let input5 :Vec<char> = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".chars().collect();
 
-        assert_eq!(solve_challenge(input1, 14), 19);
-        assert_eq!(solve_challenge(input2, 14), 23);
-        assert_eq!(solve_challenge(input3, 14), 23);
-        assert_eq!(solve_challenge(input4, 14), 29);
-        assert_eq!(solve_challenge(input5, 14), 26);
+        assert_eq!(solve_challenge(&input1, 14), Some(19));
+        assert_eq!(solve_challenge(&input2, 14), Some(23));
+        assert_eq!(solve_challenge(&input3, 14), Some(23));
+        assert_eq!(solve_challenge(&input4, 14), Some(29));
+        assert_eq!(solve_challenge(&input5, 14), Some(26));
     }
-}
-
-fn parse_input(filepath: &str) -> Vec<char> {
-    let data: String = fs::read_to_string(filepath).expect("Cannot read file");
-    data.chars().collect()
-}
 
-fn solve_challenge(data: Vec<char>, winsize: usize) -> usize {
-    let mut i = 0;
+    #[test]
+    fn test_no_marker() {
+        let data: Vec<char> = "abcabcabcd".chars().collect();
+        assert_eq!(solve_challenge(&data, 4), Some(10));
+        assert_eq!(solve_challenge(&data, 5), None);
+        assert_eq!(solve_challenge(&data, 27), None);
 
-    while i < data.len() {
-        let window = &data[i..i+winsize];
-        let foo:HashSet<char> = HashSet::from_iter(window.iter().cloned());
-        if window.len() == foo.len() {
-            return i + winsize;
-        }
-        i+=1;
+        let mut params = Day06.params();
+        params.set("part1_window", "5").unwrap();
+        assert_eq!(Day06.check_params(&data, 1, &params), Err("No marker of 5 distinct characters in the datastream".to_string()));
+        assert_eq!(Day06.check_params(&data, 2, &params), Err("No marker of 14 distinct characters in the datastream".to_string()));
+        assert_eq!(Day06.explore(&data, "window", &["27"]), Some(Err("No marker of 27 distinct characters in the datastream".to_string())));
+        assert_eq!(Day06.explore(&data, "window", &["4"]), Some(Ok("10".to_string())));
     }
-    panic!("Invalid input");
 }
-
-pub fn part1() {
-    let data = parse_input("./src/day06/input.txt");
-    println!("DATA: {:?}", data);
-    let result = solve_challenge(data, 4);
-    
-    println!("ANS: {}", result);
-}
-
-pub fn part2() {
-    let data = parse_input("./src/day06/input.txt");
-    let result = solve_challenge(data, 14);
-    println!("ANS: {}", result);
-}
\ No newline at end of file
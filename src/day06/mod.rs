@@ -1 +1,3 @@
-pub mod day06;
\ No newline at end of file
+pub mod day06;
+
+pub use self::day06::{Day06, parse_input, solve_challenge};
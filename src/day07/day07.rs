@@ -1,7 +1,13 @@
-use std::{fs};
-
 use petgraph::{Graph, stable_graph::NodeIndex, visit::{IntoNodeReferences}, Direction::{Incoming, Outgoing}};
 
+use crate::answer::Answer;
+use crate::error::{AocError, Result, parse_at};
+use crate::params::Params;
+use crate::parse::lines;
+use crate::repl::parse_arg;
+use crate::solution::{Explored, Solution};
+
+pub struct Day07;
 
 #[cfg(test)]
 mod tests {
@@ -56,7 +62,6 @@ mod tests {
                     _ => panic!("unreachable"),
                 };
                 assert_eq!(size, expected);
-                //println!("{}: {}", inode.name, size);
             }
         }
         
@@ -64,6 +69,17 @@ mod tests {
     }
 
 
+    #[test]
+    fn test_dir_sizes() {
+        let fs = parse_input(&crate::input::InputSource::Sample.read(7).unwrap()).unwrap();
+        assert_eq!(fs.dir_sizes(), vec![
+            ("/".to_string(), 48381165),
+            ("/a/".to_string(), 94853),
+            ("/a/e/".to_string(), 584),
+            ("/d/".to_string(), 24933642),
+        ]);
+    }
+
     #[test]
     fn test_part2_solver() {
         let mut fs = FileSystem::new();
@@ -71,51 +87,76 @@ mod tests {
         cd_or_create_dir(&mut fs, cwd, "foo");
         fs.cd(cwd, "foo").unwrap();
     }
+
+    #[test]
+    fn test_part2_params() {
+        let fs = parse_input("$ cd /\n$ ls\n100 a\ndir b\n$ cd b\n$ ls\n50 c\n").unwrap();
+        let mut params = Day07.params();
+        params.set("disk_size", "200").unwrap();
+        params.set("space_needed", "90").unwrap();
+        assert_eq!(Day07.check_params(&fs, 2, &params), Ok(()));
+        assert_eq!(Day07.part2_with(&fs, &params), Answer::from(50));
+
+        // Deleting everything is just enough
+        params.set("disk_size", "150").unwrap();
+        params.set("space_needed", "150").unwrap();
+        assert_eq!(Day07.check_params(&fs, 2, &params), Ok(()));
+        assert_eq!(Day07.part2_with(&fs, &params), Answer::from(150));
+
+        params.set("space_needed", "151").unwrap();
+        assert_eq!(Day07.check_params(&fs, 2, &params), Err("No directory frees enough space, 151 more is needed".to_string()));
+        params.set("disk_size", "149").unwrap();
+        assert_eq!(Day07.check_params(&fs, 2, &params), Err("150 is used, more than the disk size of 149".to_string()));
+        params.set("disk_size", "99").unwrap();
+        assert_eq!(Day07.check_params(&fs, 1, &params), Ok(()));
+    }
 }
 
-fn parse_input(filepath: &str) -> FileSystem {
-    let data: String = fs::read_to_string(filepath).expect("Cannot read file");
-    let data:Vec<_> = data.trim().split("\n").collect();    
+pub fn parse_input(data: &str) -> Result<FileSystem> {
     let mut fs:FileSystem = FileSystem::new();
     let mut cwd = fs.root;
-    for line in data {
-        if line.starts_with("$") {
-            let cmd = &line[2..4];
-            if cmd == "cd" {
-                let dir = &line[5..];
-                cwd = cd_or_create_dir(&mut fs, cwd, dir);
+    for line in lines(data) {
+        if let Some(cmd) = line.strip_prefix("$ ") {
+            if let Some(dir) = cmd.strip_prefix("cd ") {
+                if dir == ".." && fs.cd(cwd, dir).is_none() {
+                    return Err(AocError::at(data, dir, "Cannot cd .. from the root directory"));
+                }
+                // Otherwise "$ cd /" would create a directory named / in the root
+                cwd = if dir == "/" { fs.root } else { cd_or_create_dir(&mut fs, cwd, dir) };
+            } else if cmd != "ls" {
+                return Err(AocError::at(data, cmd, format!("Unknown command '{}'", cmd)));
             }
         } else {
             // If line does not start with '$', we have directory listing
-            let ls_entry = line.split(" ").collect::<Vec<&str>>();
+            let (first, name) = line.split_once(' ')
+                .ok_or_else(|| AocError::at(data, line, "Expected 'dir <name>' or '<size> <name>'"))?;
 
             // If entry starts with "dir" create new dir under cwd
-            if ls_entry[0].trim() == "dir" {
-                fs.add_child(cwd, Inode::new_dir(ls_entry[1].to_owned()));
+            if first == "dir" {
+                fs.add_child(cwd, Inode::new_dir(name.to_owned()));
                 continue;
             } else {
                 // If entry is file, create a new file under cwd
-                let size = ls_entry[0].parse::<u32>().unwrap();
-                let filename = ls_entry[1];
-                fs.add_child(cwd, Inode::new_file(filename.to_owned(), size));
+                let size = parse_at::<u32>(data, first)?;
+                fs.add_child(cwd, Inode::new_file(name.to_owned(), size));
             }
         }
     }
-    fs
+    Ok(fs)
 }
 
 fn _change_cwd(mut cwd: String, cdval: &str) -> String {
     if cdval.starts_with("/") {
         cwd = String::from(cdval);
     } else if cdval == ".." {
-        let foo = cwd.split("/").collect::<Vec<&str>>();
-        let foo = &foo[..foo.len()-1];
-        cwd = foo.join("/");
-        if cwd == "" {
+        let parts = cwd.split("/").collect::<Vec<&str>>();
+        let parts = &parts[..parts.len()-1];
+        cwd = parts.join("/");
+        if cwd.is_empty() {
             cwd = "/".to_owned();
         }
     } else {
-        if cwd.len() == 1 && cwd.chars().nth(0).unwrap() == '/' {
+        if cwd.len() == 1 && cwd.starts_with('/') {
             cwd = format!("/{}", cdval);
         } else {
             cwd = format!("{}/{}", cwd,cdval);
@@ -125,12 +166,12 @@ fn _change_cwd(mut cwd: String, cdval: &str) -> String {
 }
 #[derive(PartialEq)]
 #[derive(Debug)]
-enum FType {
+pub enum FType {
     DIR,
     FILE,
 }
 #[derive(Debug)]
-struct Inode {
+pub struct Inode {
     name: String,
     size: u32,
     filetype: FType, 
@@ -150,7 +191,7 @@ impl Inode {
     }
 }
 
-struct FileSystem {
+pub struct FileSystem {
     fsgraph: Graph<Inode, Child>,
     root: NodeIndex,    
 }
@@ -168,6 +209,10 @@ impl FileSystem {
         FileSystem { fsgraph, root }
     }
 
+    pub fn root(&self) -> NodeIndex {
+        self.root
+    }
+
     // Only supports relative path in one level
     pub fn cd(&self, cwd: NodeIndex, path: &str) -> Option<NodeIndex> {
         if path == ".." {
@@ -207,6 +252,29 @@ impl FileSystem {
         size
     }
 
+    // Total size of every directory by its path, sorted by path
+    pub fn dir_sizes(&self) -> Vec<(String, u32)> {
+        let mut sizes = vec![];
+        let mut stack = vec![(self.root, "/".to_string())];
+        while let Some((dir, path)) = stack.pop() {
+            for child in self.fsgraph.neighbors_directed(dir, Outgoing) {
+                let inode = &self.fsgraph[child];
+                if inode.filetype == FType::DIR {
+                    stack.push((child, format!("{}{}/", path, inode.name)));
+                }
+            }
+            sizes.push((path, self.get_children_size(dir)));
+        }
+        sizes.sort();
+        sizes
+    }
+
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        FileSystem::new()
+    }
 }
 
 struct Child;
@@ -214,7 +282,7 @@ struct Child;
 fn cd_or_create_dir(fs: &mut FileSystem, cwd: NodeIndex, path: &str) -> NodeIndex {
     match fs.cd(cwd, path) {
         Some(i) => {
-            return i;
+            i
         },
         None => {
             if path == ".." {
@@ -227,45 +295,96 @@ fn cd_or_create_dir(fs: &mut FileSystem, cwd: NodeIndex, path: &str) -> NodeInde
     }
 }
 
-pub fn part1() {
-    let fs = parse_input("./src/day07/input.txt");
+// Space the update still needs, 0 if there is enough free already
+fn space_to_free(fs: &FileSystem, params: &Params) -> std::result::Result<u32, String> {
+    let occupied = fs.get_children_size(fs.root);
+    let total: u32 = params.int("disk_size");
+    let needed: u32 = params.int("space_needed");
+    let free = total.checked_sub(occupied)
+        .ok_or_else(|| format!("{} is used, more than the disk size of {}", occupied, total))?;
+    Ok(needed.saturating_sub(free))
+}
 
-    // Parsing is done    
-    let mut result = 0;
+// Size of the smallest directory whose deletion frees need_space
+fn smallest_dir_freeing(fs: &FileSystem, need_space: u32) -> Option<u32> {
+    let mut result = None;
     for (index, inode) in fs.fsgraph.node_references() {
         if inode.filetype == FType::DIR {
             let size = fs.get_children_size(index);
-            if size < 100000 {
-                result += size;
+            if size >= need_space && result.is_none_or(|x| size < x) {
+                result = Some(size);
             }
         }
     }
-    //ANS: 1908462
-    println!("ANS: {:?}", result);
+    result
 }
 
-pub fn part2() {
-    let fs = parse_input("./src/day07/input.txt");
-    let mut result = 0xffffffff;
+impl Solution for Day07 {
+    type Input = FileSystem;
 
-    // Parsing is done    
-    let occupied = fs.get_children_size(fs.root);
-    let total = 70000000;
-    let free = total - occupied;
-    if free > 30000000 {
-        panic!("Unreachable!");
+    fn parse(&self, data: &str) -> Result<Self::Input> {
+        parse_input(data)
     }
-    let need_space = 30000000 - free;
-    
-    for (index, inode) in fs.fsgraph.node_references() {
-        if inode.filetype == FType::DIR {
-            let size = fs.get_children_size(index);
-            if size > need_space && size < result{
-                result = size;
-            }            
+
+    fn part1(&self, fs: &Self::Input) -> Answer {
+        let mut result = 0;
+        for (index, inode) in fs.fsgraph.node_references() {
+            if inode.filetype == FType::DIR {
+                let size = fs.get_children_size(index);
+                if size < 100000 {
+                    result += size;
+                }
+            }
+        }
+        Answer::from(result)
+    }
+
+    fn part2(&self, fs: &Self::Input) -> Answer {
+        self.part2_with(fs, &self.params())
+    }
+
+    fn params(&self) -> Params {
+        Params::new()
+            .declare("disk_size", 70000000, 0..=u32::MAX as i64, "Total space of the device")
+            .declare("space_needed", 30000000, 0..=u32::MAX as i64, "Free space the update needs")
+    }
+
+    fn part2_with(&self, fs: &Self::Input, params: &Params) -> Answer {
+        // check_params made sure there is an answer
+        let need_space = space_to_free(fs, params).unwrap();
+        if need_space == 0 {
+            // Nothing needs to be deleted
+            return Answer::from(0);
+        }
+        Answer::from(smallest_dir_freeing(fs, need_space).unwrap())
+    }
+
+    fn check_params(&self, fs: &Self::Input, part: u8, params: &Params) -> std::result::Result<(), String> {
+        if part != 2 {
+            return Ok(());
+        }
+        let need_space = space_to_free(fs, params)?;
+        if need_space > 0 && smallest_dir_freeing(fs, need_space).is_none() {
+            return Err(format!("No directory frees enough space, {} more is needed", need_space));
         }
+        Ok(())
     }
-    
-    //ANS: 1908462
-    println!("ANS: {:?}", result);
-}
\ No newline at end of file
+
+    fn explore(&self, fs: &Self::Input, command: &str, args: &[&str]) -> Option<Explored> {
+        match command {
+            "sizes" => {
+                let max = if args.is_empty() { Ok(u32::MAX) } else { parse_arg::<u32>(args, 0, "max") };
+                Some(max.map(|max| fs.dir_sizes().iter()
+                    .filter(|(_, size)| *size <= max)
+                    .map(|(path, size)| format!("{:>10} {}", size, path))
+                    .collect::<Vec<_>>()
+                    .join("\n")))
+            },
+            _ => None,
+        }
+    }
+
+    fn explore_help(&self) -> &'static [(&'static str, &'static str)] {
+        &[("sizes [max]", "Size of every directory, or only of those up to [max]")]
+    }
+}
@@ -1 +1,3 @@
-pub mod day07;
\ No newline at end of file
+pub mod day07;
+
+pub use self::day07::{Day07, FType, FileSystem, Inode, parse_input};
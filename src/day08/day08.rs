@@ -1,12 +1,20 @@
-use std::{fs};
+use crate::answer::Answer;
+use crate::error::Result;
+use crate::grid::Grid;
+use crate::solution::Solution;
+
+pub struct Day08;
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
 
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     //use super::*;
 
     use crate::day08::day08::{Forest, parse_input};
+    use crate::grid::Grid;
+    use crate::input::InputSource;
 
     #[test]
     fn test_count_visible_trees() {         
@@ -18,7 +26,7 @@ mod tests {
             vec![3, 5, 3, 9, 0],
         ];        
 
-        let f = Forest {forest: forest, height: 5, width: 5};
+        let f = Forest::new(Grid::from_rows(forest));
         assert_eq!(f.is_visible(1, 1), true);
         assert_eq!(f.is_visible(1, 2), true);
         assert_eq!(f.is_visible(1, 3), false);
@@ -28,8 +36,7 @@ mod tests {
         assert_eq!(f.is_visible(3, 1), false);
         assert_eq!(f.is_visible(3, 2), true);
         assert_eq!(f.is_visible(3, 3), false);
-        let result = f.solve_part1();
-        println!("TADA {}", result);
+        assert_eq!(f.solve_part1(), 21);
     }
 
 
@@ -43,26 +50,24 @@ mod tests {
             vec![3, 5, 3, 9, 0],
         ];
 
-        let foo = parse_input("./src/day08/sample_input.txt");
+        let foo = parse_input(&InputSource::Sample.read(8).unwrap()).unwrap();
 
-        let f = Forest {forest: forest, height: 5, width: 5};
-        for i in 0..5 {
-            assert_eq!(foo.forest[i], f.forest[i]);
-        }
-        assert_eq!(f.height, foo.height);
-        assert_eq!(f.width, foo.width);
+        let f = Forest::new(Grid::from_rows(forest));
+        assert_eq!(foo.forest, f.forest);
+        assert_eq!(f.height(), foo.height());
+        assert_eq!(f.width(), foo.width());
     }
 
     #[test]
     fn test_9_92() {
-        let forest = parse_input("./src/day08/input.txt");
+        let forest = parse_input(&InputSource::Default.read(8).unwrap()).unwrap();
         let result = forest.is_visible(9, 92);
         assert_eq!(result, false);
     }
 
     #[test]
     fn part2_2_2_scores() {
-        let forest = parse_input("./src/day08/sample_input.txt");
+        let forest = parse_input(&InputSource::Sample.read(8).unwrap()).unwrap();
         let (_, score) = forest.get_stats_up(1, 2);
         assert_eq!(score, 1);
 
@@ -81,54 +86,55 @@ mod tests {
 
 #[derive(PartialEq,Eq)]
 #[derive(Debug)]
-struct Forest {
-    forest: Vec<Vec<u8>>,
-    height: usize,
-    width: usize,
+pub struct Forest {
+    // Tree heights
+    forest: Grid<u8>,
 }
 
 impl Forest {
-    fn get_stats_right(&self, row: usize, col: usize) -> (bool, usize) {
-        let me = self.forest[row][col];
-        for j in col+1..self.width {
-            if self.forest[row][j] >= me {
-                return (false, j - col);
+    pub fn new(forest: Grid<u8>) -> Self {
+        Forest { forest }
+    }
+
+    pub fn height(&self) -> usize {
+        self.forest.height()
+    }
+
+    pub fn width(&self) -> usize {
+        self.forest.width()
+    }
+
+    // Whether the tree can be seen from the edge looking against step,
+    // and how many trees it sees itself looking along step
+    fn get_stats(&self, row: usize, col: usize, step: (i64, i64)) -> (bool, usize) {
+        let me = self.forest[(row, col)];
+        let mut seen = 0;
+        for pos in self.forest.ray(row, col, step) {
+            seen += 1;
+            if self.forest[pos] >= me {
+                return (false, seen);
             }
         }
-        (true, self.width - col - 1)
+        (true, seen)
+    }
+
+    fn get_stats_right(&self, row: usize, col: usize) -> (bool, usize) {
+        self.get_stats(row, col, (0, 1))
     }
 
     fn get_stats_left(&self, row: usize, col: usize) -> (bool, usize) {
-        let me = self.forest[row][col];
-        for j in (0..col).rev() {
-            if self.forest[row][j] >= me {
-                return (false, col - j);
-            }
-        }
-        (true, col)
+        self.get_stats(row, col, (0, -1))
     }
 
     fn get_stats_down(&self, row: usize, col: usize) -> (bool, usize) {
-        let me = self.forest[row][col];
-        for i in row+1..self.height {
-            if self.forest[i][col] >= me {
-                return (false, i - row);
-            }
-        }
-        (true, self.height - row - 1)
+        self.get_stats(row, col, (1, 0))
     }
 
     fn get_stats_up(&self, row: usize, col: usize) -> (bool, usize) {
-        let me = self.forest[row][col];
-        for i in (0..row).rev() {
-            if self.forest[i][col] >= me {
-                return (false, row - i);
-            }
-        }
-        (true, row)
+        self.get_stats(row, col, (-1, 0))
     }
 
-    fn is_visible(&self, row: usize, col: usize) -> bool {
+    pub fn is_visible(&self, row: usize, col: usize) -> bool {
         let (left, _) = self.get_stats_left(row, col);
         let (right, _) = self.get_stats_right(row, col);
         let (up, _) = self.get_stats_up(row, col);
@@ -137,7 +143,7 @@ impl Forest {
         left || right || up || down
     }
 
-    fn get_scenic_score(&self, row: usize, col: usize) -> usize {
+    pub fn get_scenic_score(&self, row: usize, col: usize) -> usize {
         let (_, score_left) = self.get_stats_left(row, col);
         let (_, score_right) = self.get_stats_right(row, col);
         let (_, score_up) = self.get_stats_up(row, col);
@@ -146,10 +152,10 @@ impl Forest {
         score_left * score_right * score_up * score_down
     } 
 
-    fn solve_part1(&self) -> i32 {
+    pub fn solve_part1(&self) -> i32 {
         let mut result = 0;
-        let height = self.forest.len();
-        let width = self.forest[0].len();
+        let height = self.height();
+        let width = self.width();
         
         // Add all the trees in perimeter
         result += 2 * height + 2 * width - 4;
@@ -157,7 +163,7 @@ impl Forest {
         for row in 1..height-1{
             for col in 1..width-1 {
                 if self.is_visible(row, col) {
-                    //println!("[{}][{}] = {}", row, col, self.forest[row][col]);
+                    trace!("[{}][{}] = {} is visible", row, col, self.forest[(row, col)]);
                     result += 1;
                 }
             }
@@ -165,10 +171,10 @@ impl Forest {
         result as i32
     }
 
-    fn solve_part2(&self) -> i32 {
+    pub fn solve_part2(&self) -> i32 {
         let mut result = 0;
-        let height = self.forest.len();
-        let width = self.forest[0].len();
+        let height = self.height();
+        let width = self.width();
 
         for row in 1..height-1{
             for col in 1..width-1 {
@@ -182,27 +188,28 @@ impl Forest {
     }
 }
 
-fn parse_input(filepath: &str) -> Forest {
-    let data: String = fs::read_to_string(filepath).expect("Cannot read file");
-    let data:Vec<_> = data.trim().split("\n").collect();
-    let data:Vec<Vec<_>> = data.iter()
-                                .map(|x| x.chars()
-                                    .map(|y| y.to_digit(10).unwrap() as u8)
-                                    .collect())
-                                .collect();
-    Forest {height: data.len(), width: data[0].len(), forest: data, }
-
+pub fn parse_input(data: &str) -> Result<Forest> {
+    let forest = Grid::parse(data, |c| match c.to_digit(10) {
+        Some(height) => Ok(height as u8),
+        None => Err(format!("Unexpected tree height '{}'", c)),
+    })?;
+    Ok(Forest::new(forest))
 }
 
-pub fn part1() {
-    //let f = parse_input("./src/day08/sample_input.txt");
-    let f = parse_input("./src/day08/input.txt");
-    let result = f.solve_part1();
-    println!("ANS: {:?}", result);
-}
+impl Solution for Day08 {
+    type Input = Forest;
 
-pub fn part2() {
-    let fs = parse_input("./src/day08/input.txt");
-    let result = fs.solve_part2();
-    println!("ANS: {:?}", result);
-}
\ No newline at end of file
+    fn parse(&self, data: &str) -> Result<Self::Input> {
+        parse_input(data)
+    }
+
+    fn part1(&self, forest: &Self::Input) -> Answer {
+        let result = forest.solve_part1();
+        Answer::from(result)
+    }
+
+    fn part2(&self, forest: &Self::Input) -> Answer {
+        let result = forest.solve_part2();
+        Answer::from(result)
+    }
+}
@@ -1 +1,3 @@
-pub mod day08;
\ No newline at end of file
+pub mod day08;
+
+pub use self::day08::{Day08, Forest, parse_input};
@@ -1,14 +1,14 @@
-use std::{fs, collections::HashMap};
+use std::collections::{HashMap, HashSet};
 
-#[derive(Copy, Clone)]
-#[derive(PartialEq,Eq)]
-#[derive(Debug)]
-enum Direction {
-    West,
-    East,
-    North,
-    South,
-}
+use crate::answer::Answer;
+use crate::error::{AocError, Result, parse_at};
+use crate::geometry::{Direction, Point};
+use crate::params::Params;
+use crate::parse::lines;
+use crate::render::{Animation, Colour, Render, Viewport};
+use crate::solution::Solution;
+
+pub struct Day09;
 
 #[derive(Copy, Clone)]
 #[derive(PartialEq,Eq)]
@@ -26,181 +26,223 @@ enum RelativePosition {
     NoPos,
 }
 
-#[derive(Hash)]
-#[derive(Copy, Clone)]
-#[derive(PartialEq,Eq)]
-#[derive(Debug)]
-struct Position {
-    row: i32,
-    col: i32,
-}
-
 #[derive(PartialEq,Eq)]
 #[derive(Debug)]
-struct Instruction {
+#[derive(Clone)]
+pub struct Instruction {
     direction: Direction,
     distance: u32,
 }
 
 impl Instruction {
-    fn new(direction: Direction, distance: u32) -> Self {
+    pub fn new(direction: Direction, distance: u32) -> Self {
         Instruction {direction, distance}
     }
 }
 
 
-impl Position {
-    fn new(row:i32, col:i32) -> Self {
-        Position { row, col }    
-    }
-
-    fn move_one(&mut self, direction: Direction ) {
-        match direction {
-            Direction::East => {
-                self.move_east();
-            },
-            Direction::West => {
-                self.move_west();
-            },
-            Direction::North => {
-                self.move_north();
-            },
-            Direction::South => {
-                self.move_south();
-            },
-        }
-    }
-
-    fn move_north(&mut self) {
-        self.row -= 1;
-    }
-
-    fn move_south(&mut self) {
-        self.row += 1;
-    }
-
-    fn move_east(&mut self) {
-        self.col += 1;
-    }
-
-    fn move_west(&mut self) {
-        self.col -= 1;
-    }
-
-}
-
 #[derive(PartialEq,Eq)]
 #[derive(Debug)]
-struct PlayGround {
+pub struct PlayGround {
     // Need negative int here as the rope 
     // can travel in all 4 quadrants
-    head: Position,
-    tail: Position,
-    counter: HashMap<Position, bool>,
+    head: Point,
+    tail: Point,
+    counter: HashMap<Point, bool>,
 }
 
 impl PlayGround {
-    fn new() -> Self {
-        let head = Position::new(0, 0);
-        let tail = Position::new(0, 0);
+    pub fn new() -> Self {
+        let head = Point::new(0, 0);
+        let tail = Point::new(0, 0);
         let mut counter = HashMap::new(); 
         counter.insert(tail, true);
         PlayGround { head, tail, counter }
     }
 
+    pub fn head(&self) -> Point {
+        self.head
+    }
+
+    pub fn tail(&self) -> Point {
+        self.tail
+    }
+
+    // Number of positions the tail has been on so far
+    pub fn visited(&self) -> usize {
+        self.counter.len()
+    }
+
     // Gets the relative position of the tail with respect to 
     // the current head position
     fn get_relative_position(&self) -> RelativePosition {
-        if self.head == self.tail {
-            return RelativePosition::Overlapping;
+        let diff = self.tail - self.head;
+        match (diff.x, diff.y) {
+            (0, 0) => RelativePosition::Overlapping,
+            (-1, 0) => RelativePosition::West,
+            (1, 0) => RelativePosition::East,
+            (0, -1) => RelativePosition::North,
+            (0, 1) => RelativePosition::South,
+            // Cross positions
+            (-1, -1) => RelativePosition::NorthWest,
+            (1, -1) => RelativePosition::NorthEast,
+            (-1, 1) => RelativePosition::SouthWest,
+            (1, 1) => RelativePosition::SouthEast,
+            _ => RelativePosition::NoPos,
         }
+    } 
 
-        if self.head.row == self.tail.row && self.tail.col + 1 == self.head.col {
-                return RelativePosition::West;
+    pub fn execute_instruction(&mut self, instruction:Instruction) {
+        let mut head_old;
+        for _ in 0..instruction.distance {
+            head_old = self.head;
+            self.head += instruction.direction.step();
+            if self.get_relative_position() == RelativePosition::NoPos {
+                self.tail = head_old;
+                self.counter.insert(self.tail, true);
+            }
         }
-        if self.head.row == self.tail.row && self.tail.col - 1 == self.head.col {
-                return RelativePosition::East;
-        }                                                                        
+    }
 
-        if self.head.col == self.tail.col && self.tail.row + 1 == self.head.row {
-                return RelativePosition::North;
-        }
-        if self.head.col == self.tail.col && self.tail.row - 1 == self.head.row {
-                return RelativePosition::South;
+    pub fn execute(&mut self, instructions:Vec<Instruction>) {
+        for op in instructions {
+            self.execute_instruction(op);
         }
+    }
+}
 
-        // Cross positions
-        if self.tail.row + 1 == self.head.row && self.tail.col + 1 == self.head.col {
-            return RelativePosition::NorthWest;
-        }
+impl Default for PlayGround {
+    fn default() -> Self {
+        PlayGround::new()
+    }
+}
 
-        if self.tail.row + 1 == self.head.row && self.tail.col - 1 == self.head.col {
-            return RelativePosition::NorthEast;
-        }
+// Generalised version of PlayGround for ropes with any number of knots,
+// head first
+#[derive(Debug)]
+pub struct Rope {
+    knots: Vec<Point>,
+    visited: HashSet<Point>,
+}
 
-        if self.tail.row - 1 == self.head.row && self.tail.col + 1 == self.head.col {
-            return RelativePosition::SouthWest;
-        }
+impl Rope {
+    pub fn new(knots: usize) -> Self {
+        let knots = vec![Point::new(0, 0); knots];
+        let visited = HashSet::from([knots[knots.len() - 1]]);
+        Rope { knots, visited }
+    }
 
-        if self.tail.row - 1 == self.head.row && self.tail.col - 1 == self.head.col {
-            return RelativePosition::SouthEast;
+    // Moves the head one step, the other knots follow
+    pub fn step(&mut self, direction: Direction) {
+        let knots = &mut self.knots;
+        knots[0] += direction.step();
+        for i in 1..knots.len() {
+            // Knots only move when they are not touching anymore
+            if knots[i - 1].chebyshev(knots[i]) > 1 {
+                let step = (knots[i - 1] - knots[i]).signum();
+                knots[i] += step;
+            }
         }
+        self.visited.insert(knots[knots.len() - 1]);
+    }
 
+    // Number of positions the last knot has been on so far
+    pub fn visited(&self) -> usize {
+        self.visited.len()
+    }
+}
 
-        return RelativePosition::NoPos;
-    } 
+// Drawn like in the puzzle text: H for the head, then T or the number of
+// the knot, s for the start and # where the tail has been
+impl Render for Rope {
+    fn bounds(&self) -> Viewport {
+        let points = self.knots.iter().chain(self.visited.iter()).copied();
+        Viewport::around(points).expect("A rope has knots")
+    }
 
-    fn execute_instruction(&mut self, instruction:Instruction) {
-        let mut head_old;
-        for _ in 0..instruction.distance {
-            head_old = self.head;
-            self.head.move_one(instruction.direction);
-            if self.get_relative_position() == RelativePosition::NoPos {
-                self.tail = head_old;
-                self.counter.insert(self.tail, true);
-            }
+    fn cell(&self, point: Point) -> char {
+        // Knots closer to the head hide the ones behind them
+        match self.knots.iter().position(|x| *x == point) {
+            Some(0) => 'H',
+            Some(_) if self.knots.len() == 2 => 'T',
+            Some(i) => char::from_digit(i as u32 % 10, 10).unwrap(),
+            None if point == Point::default() => 's',
+            None if self.visited.contains(&point) => '#',
+            None => '.',
         }
     }
 
-    fn execute(&mut self, instructions:Vec<Instruction>) {
-        for op in instructions {
-            self.execute_instruction(op);
+    fn colour(&self, cell: char) -> Colour {
+        match cell {
+            'H' => Colour::Red,
+            '#' => Colour::Grey,
+            's' => Colour::Cyan,
+            '.' => Colour::Plain,
+            _ => Colour::Yellow,
+        }
+    }
+}
+
+// Returns the number of positions visited by the last knot
+pub fn simulate_rope(instructions: &[Instruction], knots: usize) -> usize {
+    simulate_rope_with(instructions, knots, 0)
+}
+
+// Same as simulate_rope, showing every step at fps frames a second
+fn simulate_rope_with(instructions: &[Instruction], knots: usize, fps: u32) -> usize {
+    let mut rope = Rope::new(knots);
+    let mut animation = Animation::new(fps);
+    animation.frame(&rope);
+    for instruction in instructions {
+        for _ in 0..instruction.distance {
+            rope.step(instruction.direction);
+            animation.frame(&rope);
         }
     }
+    rope.visited()
 }
 
-fn parse_input(filepath: &str) -> Vec<Instruction> {
-    let data: String = fs::read_to_string(filepath).expect("Cannot read file");
-    let data:Vec<_> = data.trim().split("\n").collect();
+pub fn parse_input(data: &str) -> Result<Vec<Instruction>> {
     let mut instructions = vec![];
-    for line in data {
-        let op:Vec<_> = line.split(" ").collect();
-        let direction = match op[0] {
-            "R" => Direction::East,
-            "L" => Direction::West,
-            "U" => Direction::North,
-            "D" => Direction::South,
-            _ => panic!("Unexpected Direction string: {}", op[0])
-        };
-        let distance:u32 = op[1].parse().unwrap();
+    for line in lines(data) {
+        let (direction, distance) = line.split_once(' ')
+            .ok_or_else(|| AocError::at(data, line, "Expected '<direction> <distance>'"))?;
+        let direction: Direction = direction.parse().map_err(|e| AocError::at(data, direction, e))?;
+        let distance:u32 = parse_at(data, distance)?;
         instructions.push(Instruction::new(direction, distance));
     }
-    instructions
+    Ok(instructions)
 }
 
-pub fn part1() {
-    let instructions = parse_input("./src/day09/input.txt");
-    let mut playground = PlayGround::new();
-    playground.execute(instructions);
-    let result = playground.counter.len();
+impl Solution for Day09 {
+    type Input = Vec<Instruction>;
 
-    println!("ANS: {:?}", result);
-}
+    fn parse(&self, data: &str) -> Result<Self::Input> {
+        parse_input(data)
+    }
+
+    fn part1(&self, instructions: &Self::Input) -> Answer {
+        self.part1_with(instructions, &self.params())
+    }
 
-pub fn part2() {
-    // let fs = parse_input("./src/day09/input.txt");
-    // let result = fs.solve_part2();
-    // println!("ANS: {:?}", result);
+    fn part2(&self, instructions: &Self::Input) -> Answer {
+        self.part2_with(instructions, &self.params())
+    }
+
+    fn params(&self) -> Params {
+        Params::new()
+            .declare("fps", 0, 0..=u32::MAX as i64, "Frames per second of an animation of the rope on stderr, 0 for none")
+    }
+
+    fn part1_with(&self, instructions: &Self::Input, params: &Params) -> Answer {
+        let result = simulate_rope_with(instructions, 2, params.int("fps"));
+        Answer::from(result)
+    }
+
+    fn part2_with(&self, instructions: &Self::Input, params: &Params) -> Answer {
+        let result = simulate_rope_with(instructions, 10, params.int("fps"));
+        Answer::from(result)
+    }
 }
 
 #[cfg(test)]
@@ -210,6 +252,8 @@ mod tests {
     //use super::*;
 
     use super::*;
+    use crate::input::InputSource;
+    use crate::render;
 
     #[test]
     fn test_sample_input() {
@@ -226,54 +270,100 @@ mod tests {
         let mut playground = PlayGround::new();
         playground.execute(instructions);
 
-        assert_eq!(Position::new(-2, 1), playground.tail);
-        assert_eq!(Position::new(-2, 2), playground.head);
+        assert_eq!(Point::new(1, -2), playground.tail);
+        assert_eq!(Point::new(2, -2), playground.head);
 
         let tail_count = playground.counter.len();
         assert_eq!(tail_count, 13);
 
     }
 
+    #[test]
+    fn test_simulate_rope() {
+        let instructions = parse_input(&InputSource::Sample.read(9).unwrap()).unwrap();
+        assert_eq!(simulate_rope(&instructions, 2), 13);
+        assert_eq!(simulate_rope(&instructions, 10), 1);
+
+        let instructions = vec![
+            Instruction::new(Direction::East, 5),
+            Instruction::new(Direction::North, 8),
+            Instruction::new(Direction::West, 8),
+            Instruction::new(Direction::South, 3),
+            Instruction::new(Direction::East, 17),
+            Instruction::new(Direction::South, 10),
+            Instruction::new(Direction::West, 25),
+            Instruction::new(Direction::North, 20),
+        ];
+        assert_eq!(simulate_rope(&instructions, 10), 36);
+    }
+
+    #[test]
+    fn test_render() {
+        let instructions = parse_input(&InputSource::Sample.read(9).unwrap()).unwrap();
+        let mut rope = Rope::new(2);
+        for instruction in &instructions {
+            for _ in 0..instruction.distance {
+                rope.step(instruction.direction);
+            }
+        }
+        assert_eq!(render::draw_cropped(&rope), vec![
+            "..##.",
+            "...##",
+            ".TH##",
+            "....#",
+            "s###.",
+        ]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_input("R 4\nX 4\n").unwrap_err();
+        assert_eq!(err.to_string(), "2:1: Unexpected direction 'X'");
+
+        let err = parse_input("R 4\nU four\n").unwrap_err();
+        assert_eq!(err.to_string(), "2:3: Cannot parse 'four' as u32");
+    }
+
     #[test]
     fn exec_one_instruction() {
         let mut playground = PlayGround::new();
         
         playground.execute_instruction(Instruction::new(Direction::East, 4));
-        assert_eq!(playground.head, Position::new(0, 4));
-        assert_eq!(playground.tail, Position::new(0, 3));
+        assert_eq!(playground.head, Point::new(4, 0));
+        assert_eq!(playground.tail, Point::new(3, 0));
 
         playground.execute_instruction(Instruction::new(Direction::North, 4));
-        assert_eq!(playground.head, Position::new(-4, 4));
-        assert_eq!(playground.tail, Position::new(-3, 4));
+        assert_eq!(playground.head, Point::new(4, -4));
+        assert_eq!(playground.tail, Point::new(4, -3));
     }
 
     #[test]
     fn relative_postitions_test() {        
-        let playground = PlayGround {tail: Position::new(1, 0), head: Position::new(1, 1), counter: HashMap::new()};
+        let playground = PlayGround {tail: Point::new(0, 1), head: Point::new(1, 1), counter: HashMap::new()};
         assert_eq!(playground.get_relative_position(), RelativePosition::West);
 
-        let playground = PlayGround {tail: Position::new(1, 2), head: Position::new(1, 1), counter: HashMap::new()};
+        let playground = PlayGround {tail: Point::new(2, 1), head: Point::new(1, 1), counter: HashMap::new()};
         assert_eq!(playground.get_relative_position(), RelativePosition::East);
 
-        let playground = PlayGround {tail: Position::new(0, 1), head: Position::new(1, 1), counter: HashMap::new()};
+        let playground = PlayGround {tail: Point::new(1, 0), head: Point::new(1, 1), counter: HashMap::new()};
         assert_eq!(playground.get_relative_position(), RelativePosition::North);
 
-        let playground = PlayGround {tail: Position::new(2, 1), head: Position::new(1, 1), counter: HashMap::new()};
+        let playground = PlayGround {tail: Point::new(1, 2), head: Point::new(1, 1), counter: HashMap::new()};
         assert_eq!(playground.get_relative_position(), RelativePosition::South);
 
-        let playground = PlayGround {tail: Position::new(0, 2), head: Position::new(1, 1), counter: HashMap::new()};
+        let playground = PlayGround {tail: Point::new(2, 0), head: Point::new(1, 1), counter: HashMap::new()};
         assert_eq!(playground.get_relative_position(), RelativePosition::NorthEast);
 
-        let playground = PlayGround {tail: Position::new(0, 0), head: Position::new(1, 1), counter: HashMap::new()};
+        let playground = PlayGround {tail: Point::new(0, 0), head: Point::new(1, 1), counter: HashMap::new()};
         assert_eq!(playground.get_relative_position(), RelativePosition::NorthWest);
 
-        let playground = PlayGround {tail: Position::new(2, 2), head: Position::new(1, 1), counter: HashMap::new()};
+        let playground = PlayGround {tail: Point::new(2, 2), head: Point::new(1, 1), counter: HashMap::new()};
         assert_eq!(playground.get_relative_position(), RelativePosition::SouthEast);
 
-        let playground = PlayGround {tail: Position::new(2, 0), head: Position::new(1, 1), counter: HashMap::new()};
+        let playground = PlayGround {tail: Point::new(0, 2), head: Point::new(1, 1), counter: HashMap::new()};
         assert_eq!(playground.get_relative_position(), RelativePosition::SouthWest);
 
-        let playground = PlayGround {tail: Position::new(1, 1), head: Position::new(1, 1), counter: HashMap::new()};
+        let playground = PlayGround {tail: Point::new(1, 1), head: Point::new(1, 1), counter: HashMap::new()};
         assert_eq!(playground.get_relative_position(), RelativePosition::Overlapping);
     }
 }
\ No newline at end of file
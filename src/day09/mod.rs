@@ -1 +1,3 @@
-pub mod day09;
\ No newline at end of file
+pub mod day09;
+
+pub use self::day09::{Day09, Instruction, PlayGround, parse_input, simulate_rope};
@@ -1,68 +1,85 @@
-use std::fs;
+use crate::answer::Answer;
+use crate::error::{AocError, Result, parse_at};
+use crate::grid::Grid;
+use crate::params::Params;
+use crate::parse::lines;
+use crate::render::{self, Animation, Render};
+use crate::solution::Solution;
+
+pub struct Day10;
 
 #[derive(PartialEq,Eq)]
 #[derive(Debug)]
-enum Op {
+#[derive(Clone)]
+pub enum Op {
     NOOP,
     ADDX,
 }
 
 #[derive(PartialEq,Eq)]
 #[derive(Debug)]
-struct OpCode {
+#[derive(Clone)]
+pub struct OpCode {
     operator: Op,
     operand: i32,
 }
 
 impl OpCode {
-    fn new(operator: Op, operand: i32) -> Self {
+    pub fn new(operator: Op, operand: i32) -> Self {
         OpCode{operator, operand}
     }
 }
 
-fn parse_input(filepath: &str) -> Vec<OpCode> {
-    let data: String = fs::read_to_string(filepath).expect("Cannot read file");
-    let data:Vec<_> = data.trim().split("\n").collect();
+pub fn parse_input(data: &str) -> Result<Vec<OpCode>> {
     let mut instructions = vec![];
-    for line in data {
+    for line in lines(data) {
         if line == "noop" {
             instructions.push(OpCode::new(Op::NOOP, 0));
-        } else {
-            let opcode:Vec<_> = line.split(" ").collect();
-            let operand:i32 = match opcode[1].parse() {
-                Ok(x) => x,
-                Err(_e) => panic!("Error reading {} as i32", opcode[1]),
-            };
+        } else if let Some(operand) = line.strip_prefix("addx ") {
+            let operand:i32 = parse_at(data, operand)?;
             instructions.push(OpCode::new(Op::ADDX, operand));
+        } else {
+            return Err(AocError::at(data, line, format!("Unknown instruction '{}'", line)));
         }
     }
-    instructions
+    Ok(instructions)
 }
 
 fn calc_strength(cycles: i32, x: i32) -> i32 {
     let spl_cycles = Vec::from_iter((20..=220).step_by(40));
     if spl_cycles.contains(&cycles) {
-        println!("Cycles: {}\tX: {}", cycles, x);
+        debug!("Cycles: {}\tX: {}", cycles, x);
         return x * cycles;
     }
     0
 }
 
-fn draw_pixel(cycles:i32, x:i32) {
+const CRT_WIDTH: usize = 40;
+
+fn draw_pixel(crt: &mut Grid<char>, cycles:i32, x:i32) {
     let mut pixel = ' ';
     if ((cycles -1) % 40) >= (x - 1) && ((cycles -1) % 40) <= (x + 1) {
         pixel = '#'
     }
-    
-    
-    
-    if (cycles - 1 ) % 40 == 0 {
-        print!("\n")
-    }
-    print!("{}", pixel);
+
+    let pos = (cycles - 1) as usize;
+    crt[(pos / CRT_WIDTH, pos % CRT_WIDTH)] = pixel;
 }
 
-fn execute(instructions: Vec<OpCode>) -> i32 {
+// Returns the signal strength and the rows drawn on the CRT
+pub fn execute(instructions: Vec<OpCode>) -> (i32, Vec<String>) {
+    execute_with(instructions, 0)
+}
+
+// Same as execute, showing every pixel being drawn at fps frames a second
+fn execute_with(instructions: Vec<OpCode>, fps: u32) -> (i32, Vec<String>) {
+    let total: usize = instructions.iter()
+        .map(|x| if x.operator == Op::ADDX { 2 } else { 1 })
+        .sum();
+    let mut crt = Grid::new(CRT_WIDTH, total.div_ceil(CRT_WIDTH), ' ');
+    // The whole screen, not just the pixels lit so far
+    let mut animation = Animation::new(fps).viewport(crt.bounds());
+
     let mut cycles = 0;
     let mut x = 1;
     let mut result = 0;
@@ -71,32 +88,53 @@ fn execute(instructions: Vec<OpCode>) -> i32 {
             Op::NOOP => {
                 cycles += 1;
                 result += calc_strength(cycles, x);
-                draw_pixel(cycles, x);
+                draw_pixel(&mut crt, cycles, x);
+                animation.frame(&crt);
             },
             Op::ADDX => {
                 for _ in 0..2 {
                     cycles += 1;
                     result += calc_strength(cycles, x);
-                    draw_pixel(cycles, x);
+                    draw_pixel(&mut crt, cycles, x);
+                    animation.frame(&crt);
                 }
                 x += opcode.operand;
             }
         }
-        //println!("DEBUG: cycles: {} X: {}", cycles, x);
+        trace!("Cycles: {} X: {}", cycles, x);
     }
-    result
+    (result, render::draw(&crt, crt.bounds(), false))
 }
 
-pub fn part1() {
-    let instructions = parse_input("./src/day10/input.txt");
-    let result = execute(instructions);
-    println!("ANS: {:?}", result);
-}
+impl Solution for Day10 {
+    type Input = Vec<OpCode>;
+
+    fn parse(&self, data: &str) -> Result<Self::Input> {
+        parse_input(data)
+    }
 
-pub fn part2() {
-    // let fs = parse_input("./src/day09/input.txt");
-    // let result = fs.solve_part2();
-    // println!("ANS: {:?}", result);
+    fn part1(&self, instructions: &Self::Input) -> Answer {
+        self.part1_with(instructions, &self.params())
+    }
+
+    fn part2(&self, instructions: &Self::Input) -> Answer {
+        self.part2_with(instructions, &self.params())
+    }
+
+    fn params(&self) -> Params {
+        Params::new()
+            .declare("fps", 0, 0..=u32::MAX as i64, "Frames per second of an animation of the CRT on stderr, 0 for none")
+    }
+
+    fn part1_with(&self, instructions: &Self::Input, params: &Params) -> Answer {
+        let (result, _) = execute_with(instructions.clone(), params.int("fps"));
+        Answer::from(result)
+    }
+
+    fn part2_with(&self, instructions: &Self::Input, params: &Params) -> Answer {
+        let (_, crt) = execute_with(instructions.clone(), params.int("fps"));
+        Answer::Grid(crt)
+    }
 }
 
 #[cfg(test)]
@@ -106,6 +144,7 @@ mod tests {
     //use super::*;
 
     use super::*;
+    use crate::input::InputSource;
 
     #[test]
     fn test_sample_input() {
@@ -116,4 +155,14 @@ mod tests {
         ];
         execute(instructions);
     }
+
+    #[test]
+    fn test_crt() {
+        let instructions = parse_input(&InputSource::Sample.read(10).unwrap()).unwrap();
+        let (result, crt) = execute(instructions);
+        assert_eq!(result, 13140);
+        assert_eq!(crt.len(), 6);
+        assert_eq!(crt[0], "##  ##  ##  ##  ##  ##  ##  ##  ##  ##  ");
+        assert_eq!(crt[5], "#######       #######       #######     ");
+    }
 }
\ No newline at end of file
@@ -1 +1,3 @@
-pub mod day10;
\ No newline at end of file
+pub mod day10;
+
+pub use self::day10::{Day10, Op, OpCode, execute, parse_input};
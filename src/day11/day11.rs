@@ -1,9 +1,16 @@
-use std::fs;
+use crate::answer::Answer;
+use crate::error::{AocError, Result, parse_at};
+use crate::parse::{blocks, ints, lines};
+use crate::params::Params;
+use crate::repl::parse_arg;
+use crate::solution::{Explored, Solution};
+
+pub struct Day11;
 
 #[derive(PartialEq,Eq)]
 #[derive(Debug)]
 #[derive(Clone)]
-enum Op {
+pub enum Op {
     ADD,
     MUL,
 }
@@ -11,22 +18,28 @@ enum Op {
 #[derive(PartialEq,Eq)]
 #[derive(Debug)]
 #[derive(Clone)]
-struct MonkeyDecider {
+pub struct MonkeyDecider {
     divisibleby: u64,
     iftrue: u64,
     iffalse: u64,
 }
 
 impl MonkeyDecider {
+    // Throws to monkey iftrue when the worry level is divisible by
+    // divisibleby and to iffalse otherwise
+    pub fn new(divisibleby: u64, iftrue: u64, iffalse: u64) -> Self {
+        MonkeyDecider { divisibleby, iftrue, iffalse }
+    }
+
     fn decide(&self, value: u64) -> bool {
-        value % (self.divisibleby as u64) == 0
+        value.is_multiple_of(self.divisibleby)
     }
 }
 
 #[derive(PartialEq,Eq)]
 #[derive(Debug)]
 #[derive(Clone)]
-struct Operation {
+pub struct Operation {
     operator: Op,
     operand1: String,
     operand2: String,
@@ -40,38 +53,51 @@ impl Operation {
         }
     }
 
-    fn execute(&self, old: u64) -> u64 {
+    // None when the new worry level does not fit into a u64
+    pub fn execute(&self, old: u64) -> Option<u64> {
         let op1 = Operation::resolve_operand(&self.operand1, old);
         let op2 = Operation::resolve_operand(&self.operand2, old);
         match self.operator {
             Op::ADD => {
-                op1 + op2
+                op1.checked_add(op2)
             },
             Op::MUL => {
-                op1 * op2
+                op1.checked_mul(op2)
             }
         }
     }
 
-    fn new(input:&str) -> Self {
-        let parsed: Vec<_> = input.split(" ").collect();
+    fn check_operand(data: &str, operand: &str) -> Result<String> {
+        if operand != "old" {
+            parse_at::<u64>(data, operand)?;
+        }
+        Ok(String::from(operand))
+    }
+
+    // Parses "old * 19", a slice of data
+    pub fn new(data: &str, input:&str) -> Result<Self> {
+        let parsed: Vec<_> = input.trim_end().split(" ").collect();
+        if parsed.len() != 3 {
+            return Err(AocError::at(data, input, "Expected '<operand> <operator> <operand>'"));
+        }
         let op = match parsed[1] {
             "+" => Op::ADD,
             "*" => Op::MUL,
-            _ => panic!("Unexpected operator {}", parsed[1])
+            _ => return Err(AocError::at(data, parsed[1], format!("Unexpected operator '{}'", parsed[1]))),
         };
-        Operation{
-            operand1: String::from(parsed[0]),
-            operand2: String::from(parsed[2]),
+        Ok(Operation{
+            operand1: Operation::check_operand(data, parsed[0])?,
+            operand2: Operation::check_operand(data, parsed[2])?,
             operator: op,
-        }
+        })
     }
 
 }
 
 #[derive(PartialEq,Eq)]
 #[derive(Debug)]
-struct Monkey {
+#[derive(Clone)]
+pub struct Monkey {
     items: Vec<u64>,
     operation: Operation,
     decider: MonkeyDecider,
@@ -80,12 +106,26 @@ struct Monkey {
 }
 
 impl Monkey {
-    fn new(items: Vec<u64>, operation: Operation, decider: MonkeyDecider, div3: bool) -> Self {
+    pub fn new(items: Vec<u64>, operation: Operation, decider: MonkeyDecider, div3: bool) -> Self {
         let items_inspected = 0;
         Monkey { items, operation, decider, items_inspected, div3 }
     }
 
-    fn do_monkey_stuff(&mut self) -> (Vec<u64>, Vec<u64>) {
+    pub fn items(&self) -> &[u64] {
+        &self.items
+    }
+
+    pub fn items_inspected(&self) -> u64 {
+        self.items_inspected
+    }
+
+    // Whether worry levels are divided by 3 after each inspection
+    pub fn set_div3(&mut self, div3: bool) {
+        self.div3 = div3;
+    }
+
+    // None when a worry level overflows
+    fn do_monkey_stuff(&mut self) -> Option<(Vec<u64>, Vec<u64>)> {
         let mut true_vals:Vec<u64> = vec![];
         let mut false_vals:Vec<u64> = vec![];
         self.items_inspected += self.items.len() as u64;
@@ -93,9 +133,9 @@ impl Monkey {
         for i in 0..self.items.len() {
             let mut wlevel = self.items[i];
             if self.div3 {
-                wlevel = self.operation.execute(wlevel) / 3;
+                wlevel = self.operation.execute(wlevel)? / 3;
             } else {
-                wlevel = self.operation.execute(wlevel);
+                wlevel = self.operation.execute(wlevel)?;
             }
             if self.decider.decide(wlevel) {
                 true_vals.push(wlevel);
@@ -105,81 +145,99 @@ impl Monkey {
             
         }
         self.items = vec![];
-        (true_vals, false_vals)
+        Some((true_vals, false_vals))
     }
 
 }
 
-enum ParseState {
-    MonkeyIndex,
-    Items,
-    Operation,
-    DivBy,
-    IfTrue,
-    IfFalse,
-    End,
+// Text after the last sep of the line
+fn after<'a>(data: &str, line: &'a str, sep: &str) -> Result<&'a str> {
+    line.trim_end().rsplit_once(sep)
+        .map(|(_, x)| x)
+        .ok_or_else(|| AocError::at(data, line, format!("Expected '{}' in this line", sep.trim())))
 }
 
-fn parse_input(filepath: &str, div3: bool) -> Vec<Monkey> {
-    let data: String = fs::read_to_string(filepath).expect("Cannot read file");
-    let data:Vec<_> = data.trim().split("\n").collect();
+pub fn parse_input(data: &str, div3: bool) -> Result<Vec<Monkey>> {
+    let mut monkeys: Vec<Monkey> = vec![];
+    // Monkeys thrown to, checked once all of them are known
+    let mut targets = vec![];
+    for block in blocks(data) {
+        // Monkey n:, Starting items, Operation, Test, If true, If false
+        let lines = lines(block);
+        if lines.len() != 6 {
+            return Err(AocError::at(data, block, format!("Expected 6 lines describing a monkey, got {}", lines.len())));
+        }
+        let items = ints(data, after(data, lines[1], ": ")?)?;
+        let operation = Operation::new(data, after(data, lines[2], "= ")?)?;
+        let divisor = after(data, lines[3], " ")?;
+        let iftrue = after(data, lines[4], " ")?;
+        let iffalse = after(data, lines[5], " ")?;
+        let decider = MonkeyDecider {
+            divisibleby: parse_at(data, divisor)?,
+            iftrue: parse_at(data, iftrue)?,
+            iffalse: parse_at(data, iffalse)?,
+        };
+        if decider.divisibleby == 0 {
+            return Err(AocError::at(data, divisor, "Cannot test for divisibility by 0"));
+        }
+        targets.push((iftrue, decider.iftrue));
+        targets.push((iffalse, decider.iffalse));
+        monkeys.push(Monkey::new(items, operation, decider, div3));
+    }
+    // The monkey business is about the two most active ones
+    if monkeys.len() < 2 {
+        return Err(AocError::at(data, &data[data.trim_end().len()..], format!("Expected at least 2 monkeys, got {}", monkeys.len())));
+    }
+    for (token, target) in targets {
+        if target >= monkeys.len() as u64 {
+            return Err(AocError::at(data, token, format!("There is no monkey {}, the last one is {}", target, monkeys.len() - 1)));
+        }
+    }
+    Ok(monkeys)
+}
 
-    let mut state = ParseState::MonkeyIndex;
+// Product of all divisors, None if it does not fit into a u64
+fn modulus(monkeys: &[Monkey]) -> Option<u64> {
+    monkeys.iter().try_fold(1u64, |acc, x| acc.checked_mul(x.decider.divisibleby))
+}
 
-    let mut monkeys: Vec<Monkey> = vec![];
-    
-    let mut items: Vec<u64> = vec![];
-    let mut operation:Operation = Operation::new("1 * 2");
-    let mut divby: u64 = 0;
-    let mut iftrue: u64 = 0;
-    for line in data {
-        match state {
-            ParseState::MonkeyIndex => {
-                state = ParseState::Items;
-            },
-            ParseState::Items => {
-                let (_, items_str) = line.rsplit_once(": ").unwrap();
-                items = items_str.split(", ").map(|x| x.parse::<u64>().unwrap()).collect();
-                state = ParseState::Operation;
-            },
-            ParseState::Operation => {
-                let (_, operation_str) = line.rsplit_once("= ").unwrap();
-                operation = Operation::new(operation_str);
-                state = ParseState::DivBy;
-            },
-            ParseState::DivBy => {
-                let (_, divby_str) = line.rsplit_once(" ").unwrap();
-                divby = divby_str.parse::<u64>().unwrap();
-                state = ParseState::IfTrue;
-            },
-            ParseState::IfTrue => {
-                let (_, iftrue_str) = line.rsplit_once(" ").unwrap();
-                iftrue = iftrue_str.parse::<u64>().unwrap();
-                state = ParseState::IfFalse;
-            },
-            ParseState::IfFalse => {
-                let (_, iftrue_str) = line.rsplit_once(" ").unwrap();
-                let iffalse = iftrue_str.parse::<u64>().unwrap();
-                state = ParseState::End;
-                
-                let decider = MonkeyDecider { divisibleby: divby, iftrue: iftrue, iffalse: iffalse };
-                let monkey = Monkey::new(items.clone(), operation.clone(), decider, div3);
-                monkeys.push(monkey);
-            },
-            ParseState::End => {
-                state = ParseState::MonkeyIndex;
-            }
+// Without the div by 3 worry levels stay below the modulus once they are
+// thrown, so every operation only has to fit for the largest of the
+// modulus and the starting items. A monkey inspects at most every item
+// each round, which bounds the monkey business. Saves playing all rounds
+// to find out.
+fn check_modulus(monkeys: &[Monkey], rounds: u64) -> std::result::Result<(), String> {
+    let modulus = modulus(monkeys).ok_or("The product of the divisors does not fit into 64 bits")?;
+    let largest = monkeys.iter()
+        .flat_map(|x| x.items.iter().copied())
+        .fold(modulus - 1, u64::max);
+    for (i, monkey) in monkeys.iter().enumerate() {
+        if monkey.operation.execute(largest).is_none() {
+            return Err(format!("Worry levels of monkey {} can overflow", i));
         }
-        
     }
-    monkeys
+    let items = monkeys.iter().map(|x| x.items.len() as u64).sum::<u64>();
+    let business = rounds.checked_mul(items).and_then(|x| x.checked_mul(x));
+    if business.is_none_or(|x| x > i64::MAX as u64) {
+        return Err(format!("The monkey business of {} rounds can overflow", rounds));
+    }
+    Ok(())
 }
 
-fn do_monkey_business(monkeys: Vec<Monkey>, rounds:u64) -> u64 {
+pub fn do_monkey_business(monkeys: Vec<Monkey>, rounds:u64) -> std::result::Result<u64, String> {
     let mut monkeys = monkeys;
-    for _ in 0..rounds {
+    // Without the div by 3 the worry levels overflow, so keep them
+    // modulo the product of all divisors which preserves every test
+    let modulus = modulus(&monkeys);
+    for round in 0..rounds {
         for i in 0..monkeys.len() {
-            let (truevals, falsevals) = monkeys[i].do_monkey_stuff();
+            let (mut truevals, mut falsevals) = monkeys[i].do_monkey_stuff()
+                .ok_or_else(|| format!("Worry levels overflow in round {}", round + 1))?;
+            if !monkeys[i].div3 {
+                let modulus = modulus.ok_or("The product of the divisors does not fit into 64 bits")?;
+                truevals.iter_mut().for_each(|x| *x %= modulus);
+                falsevals.iter_mut().for_each(|x| *x %= modulus);
+            }
             let iftrue = monkeys[i].decider.iftrue as usize;
             let iffalse = monkeys[i].decider.iffalse as usize;
             monkeys[iftrue].items.extend(truevals);
@@ -190,21 +248,88 @@ fn do_monkey_business(monkeys: Vec<Monkey>, rounds:u64) -> u64 {
 
     monkeys.sort_by_key(|x| x.items_inspected);
     monkeys.reverse();
-    let result = monkeys[0].items_inspected * monkeys[1].items_inspected;
-    result
+    
+    monkeys[0].items_inspected.checked_mul(monkeys[1].items_inspected)
+        .filter(|x| *x <= i64::MAX as u64)
+        .ok_or_else(|| "The monkey business is too large for an answer".to_string())
 }
 
-pub fn part1() {
-    let monkeys = parse_input("./src/day11/input.txt", true);
-    let result = do_monkey_business(monkeys, 20);
-    println!("ANS: {}", result);
-    
+// Part 1 rules, worry is divided by 3 after each inspection
+fn play_div3(monkeys: &[Monkey], rounds: u64) -> std::result::Result<u64, String> {
+    let mut monkeys = monkeys.to_vec();
+    monkeys.iter_mut().for_each(|x| x.set_div3(true));
+    do_monkey_business(monkeys, rounds)
 }
 
-pub fn part2() {
-    let monkeys = parse_input("./src/day11/sample_input.txt", false);
-    let result = do_monkey_business(monkeys, 20);
-    println!("ANS: {}", result);
+impl Solution for Day11 {
+    type Input = Vec<Monkey>;
+
+    fn parse(&self, data: &str) -> Result<Self::Input> {
+        parse_input(data, false)
+    }
+
+    fn part1(&self, monkeys: &Self::Input) -> Answer {
+        self.part1_with(monkeys, &self.params())
+    }
+
+    fn part2(&self, monkeys: &Self::Input) -> Answer {
+        self.part2_with(monkeys, &self.params())
+    }
+
+    fn params(&self) -> Params {
+        Params::new()
+            .declare("part1_rounds", 20, 0..=u32::MAX as i64, "Rounds played while worry is divided by 3")
+            .declare("part2_rounds", 10000, 0..=u32::MAX as i64, "Rounds played without dividing worry")
+    }
+
+    fn part1_with(&self, monkeys: &Self::Input, params: &Params) -> Answer {
+        // check_params played the same rounds without overflowing
+        let result = play_div3(monkeys, params.int("part1_rounds")).unwrap();
+        Answer::from(result)
+    }
+
+    fn part2_with(&self, monkeys: &Self::Input, params: &Params) -> Answer {
+        // check_params made sure the worry levels fit
+        let result = do_monkey_business(monkeys.clone(), params.int("part2_rounds")).unwrap();
+        Answer::from(result)
+    }
+
+    fn check_params(&self, monkeys: &Self::Input, part: u8, params: &Params) -> std::result::Result<(), String> {
+        if part == 1 {
+            // Dividing by 3 does not go along with a modulus, so whether the
+            // worry levels fit is only known after playing
+            play_div3(monkeys, params.int("part1_rounds")).map(|_| ())
+        } else {
+            check_modulus(monkeys, params.int("part2_rounds"))
+        }
+    }
+
+    fn explore(&self, monkeys: &Self::Input, command: &str, args: &[&str]) -> Option<Explored> {
+        match command {
+            "rounds" => Some(parse_arg::<u64>(args, 0, "rounds").and_then(|rounds| {
+                let div3 = match args.get(1) {
+                    None => false,
+                    Some(&"div3") => true,
+                    Some(x) => return Err(format!("Unexpected '{}', expected div3", x)),
+                };
+                let mut monkeys = monkeys.clone();
+                monkeys.iter_mut().for_each(|x| x.set_div3(div3));
+                do_monkey_business(monkeys, rounds).map(|x| x.to_string())
+            })),
+            "monkeys" => Some(Ok(monkeys.iter().enumerate()
+                .map(|(i, x)| format!("Monkey {}: {:?}", i, x.items()))
+                .collect::<Vec<_>>()
+                .join("\n"))),
+            _ => None,
+        }
+    }
+
+    fn explore_help(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("rounds <n> [div3]", "Monkey business after <n> rounds, dividing worry by 3 with div3"),
+            ("monkeys", "Starting items of every monkey"),
+        ]
+    }
 }
 
 #[cfg(test)]
@@ -214,55 +339,103 @@ mod tests {
     //use super::*;
 
     use super::*;
+    use crate::input::InputSource;
+
+    fn operation(inp: &str) -> Operation {
+        Operation::new(inp, inp).unwrap()
+    }
 
     #[test]
     fn test_part1() {
         let monkey0 = Monkey::new(
             vec![79, 98], 
-            Operation::new("old * 19"),
+            operation("old * 19"),
             MonkeyDecider { divisibleby: 23, iftrue: 2, iffalse: 3 },
             true,
         );
         let monkey1 = Monkey::new(
             vec![54,65,75,74], 
-            Operation::new("old + 6"),
+            operation("old + 6"),
             MonkeyDecider { divisibleby: 19, iftrue: 2, iffalse: 0 },
             true,
         );
         let monkey2 = Monkey::new(
             vec![79, 60, 97], 
-            Operation::new("old * old"),
+            operation("old * old"),
             MonkeyDecider { divisibleby: 13, iftrue: 1, iffalse: 3 },
             true,
         );
         let monkey3 = Monkey::new(
             vec![74], 
-            Operation::new("old + 3"),
+            operation("old + 3"),
             MonkeyDecider { divisibleby: 17, iftrue: 0, iffalse: 1 },
             true,
         );
 
         let monkeys = vec![monkey0, monkey1, monkey2, monkey3];
-        let result = do_monkey_business(monkeys, 20);
+        let result = do_monkey_business(monkeys, 20).unwrap();
         assert_eq!(result, 10605);
     }
 
     #[test]
     fn test_part2() {
-        let monkeys = parse_input("./src/day11/sample_input.txt", false);
-        let result = do_monkey_business(monkeys, 10000);
+        let monkeys = parse_input(&InputSource::Sample.read(11).unwrap(), false).unwrap();
+        let result = do_monkey_business(monkeys, 10000).unwrap();
         assert_eq!(result, 2713310158);
     }
 
+    #[test]
+    fn test_overflow() {
+        let monkeys = parse_input(&InputSource::Sample.read(11).unwrap(), false).unwrap();
+        let mut params = Day11.params();
+        assert_eq!(Day11.check_params(&monkeys, 1, &params), Ok(()));
+        assert_eq!(Day11.check_params(&monkeys, 2, &params), Ok(()));
+
+        // old * old outgrows the division by 3
+        params.set("part1_rounds", "200").unwrap();
+        let err = Day11.check_params(&monkeys, 1, &params).unwrap_err();
+        assert!(err.starts_with("Worry levels overflow in round "), "{}", err);
+        assert_eq!(Day11.explore(&monkeys, "rounds", &["200", "div3"]), Some(Err(err)));
+
+        params.set("part2_rounds", "4000000000").unwrap();
+        assert_eq!(Day11.check_params(&monkeys, 2, &params), Err("The monkey business of 4000000000 rounds can overflow".to_string()));
+
+        let mut huge = monkeys.clone();
+        huge[0].decider.divisibleby = u64::MAX / 2;
+        assert_eq!(check_modulus(&huge, 10000), Err("The product of the divisors does not fit into 64 bits".to_string()));
+        huge[0].decider.divisibleby = 1 << 40;
+        assert_eq!(check_modulus(&huge, 10000), Err("Worry levels of monkey 2 can overflow".to_string()));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let data = InputSource::Sample.read(11).unwrap();
+        let err = parse_input(&data.replacen("If false: throw to monkey 3", "If false: throw to monkey 7", 1), false).unwrap_err();
+        assert_eq!(err.to_string(), "6:31: There is no monkey 7, the last one is 3");
+
+        let err = parse_input(&data.replacen("divisible by 23", "divisible by 0", 1), false).unwrap_err();
+        assert_eq!(err.to_string(), "4:22: Cannot test for divisibility by 0");
+
+        let one = data.split("\n\n").next().unwrap().replace("monkey 2", "monkey 0").replace("monkey 3", "monkey 0");
+        let err = parse_input(&one, false).unwrap_err();
+        assert_eq!(err.to_string(), "6:32: Expected at least 2 monkeys, got 1");
+    }
+
     #[test]
     fn operation_test() {
-        let op = Operation::new("old * 3");
-        assert_eq!(op.execute(5), 15);
+        let op = operation("old * 3");
+        assert_eq!(op.execute(5), Some(15));
 
-        let op = Operation::new("old + 4");
-        assert_eq!(op.execute(6), 10);
+        let op = operation("old + 4");
+        assert_eq!(op.execute(6), Some(10));
         
-        let op = Operation::new("old * old");
-        assert_eq!(op.execute(9), 81);
+        let op = operation("old * old");
+        assert_eq!(op.execute(9), Some(81));
+        assert_eq!(op.execute(1 << 32), None);
+
+        let data = "  Operation: new = old / 3";
+        let err = Operation::new(data, &data[19..]).unwrap_err();
+        assert_eq!(err.to_string(), "1:24: Unexpected operator '/'");
+        assert!(Operation::new("old * x", "old * x").is_err());
     }
 }
\ No newline at end of file
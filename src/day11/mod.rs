@@ -1 +1,3 @@
-pub mod day11;
\ No newline at end of file
+pub mod day11;
+
+pub use self::day11::{Day11, Monkey, MonkeyDecider, Operation, do_monkey_business, parse_input};
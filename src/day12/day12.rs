@@ -1,298 +1,157 @@
-use std::{fs, fmt, collections::{HashMap, HashSet}};
-use petgraph::{Graph, stable_graph::NodeIndex, visit::{IntoNodeReferences, IntoEdgesDirected}, Direction::{Incoming, Outgoing}, graph::Node};
-use petgraph::dot::{Dot, Config};
+use crate::answer::Answer;
+use crate::error::{AocError, Result};
+use crate::geometry::Point;
+use crate::grid::Grid;
+use crate::log;
+use crate::search::{bfs, bfs_multi};
+use crate::solution::{Explored, Solution};
 
-#[derive(Debug)]
-struct Climb; 
+pub struct Day12;
 
 #[derive(Debug)]
-struct Forest {
-    forest: Vec<Vec<u8>>,
-    height: usize,
-    width: usize,
-    start: Position,
-    end: Position,
-    graph: Graph<Position, Climb>,
-    nodeindices: Vec<Vec<NodeIndex>>,
-    counter: HashSet<char>
-}
-
-enum Direction {
-    West,
-    East,
-    North,
-    South,
+#[derive(Clone)]
+pub struct Forest {
+    forest: Grid<u8>,
+    start: Point,
+    end: Point,
 }
 
-#[derive(PartialEq,Eq)]
-#[derive(Clone, Copy)]
-struct Position {   
-    row: i32,   // Need i32 here as invalid position can
-    col: i32,   // be negative integers
-}
-
-
-impl fmt::Debug for Position {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "({},{})", self.row, self.col)
-    }
-}
-
-impl Position {
-    fn new(row: i32, col:i32 ) -> Self {
-        Position { row, col }
-    }
-
-    fn get_position(&self, direction: Direction ) -> Position {
-        match direction {
-            Direction::East => {
-                self.get_east()
-            },
-            Direction::West => {
-                self.get_west()
-            },
-            Direction::North => {
-                self.get_north()
-            },
-            Direction::South => {
-                self.get_south()
-            },
+impl Forest {
+    fn new(forest: Grid<u8>) -> Self {
+        Forest {
+            forest, 
+            start: Point::new(0, 0),
+            end: Point::new(0, 0),
         }
     }
-
-    fn get_north(&self) -> Position {
-        Position::new(self.row - 1, self.col)
+    
+    fn get_start(&self) -> Point {
+        let start = self.forest.position(|x| *x == b'S').expect("Start not present in input");
+        Point::from_cell(start)
     }
 
-    fn get_south(&self) -> Position {
-        Position::new(self.row + 1, self.col)
+    fn get_end(&self) -> Point {
+        let end = self.forest.position(|x| *x == b'E').expect("End not present in input");
+        Point::from_cell(end)
     }
 
-    fn get_east(&self) -> Position {
-        Position::new(self.row, self.col + 1)
+    pub fn start(&self) -> Point {
+        self.start
     }
 
-    fn get_west(&self) -> Position {
-        Position::new(self.row, self.col -1)
+    pub fn end(&self) -> Point {
+        self.end
     }
 
-}
-
-impl Forest {
-    fn new(height: usize, width: usize, forest: Vec<Vec<u8>>) -> Self {
-        let mut nodeindices:Vec<Vec<NodeIndex>> = vec![];
-        let mut graph:Graph<Position, Climb> = Graph::new();
-        let counter: HashSet<char> = HashSet::new();
-        
-        for row in 0..height {
-            nodeindices.push(vec![]);
-            for col in 0..width {
-                let nodeindex = graph.add_node(Position::new(row as i32, col as i32));
-                nodeindices[row].push(nodeindex);
-            }
-        }
-
-        Forest {
-            height,
-            width,
-            forest, 
-            start: Position::new(0, 0),
-            end: Position::new(0, 0),
-            graph,
-            nodeindices,
-            counter,
-        }
-    }
-    
-    fn get_start(&self) -> Position {
-        for row in 0..self.height {
-            for col in 0..self.width {
-                if self.forest[row][col] == 'S' as u8 {
-                    return Position::new(row as i32, col as i32);
-                }
-            }
-        }
-        panic!("Start not present in input");
+    pub fn height(&self) -> usize {
+        self.forest.height()
     }
 
-    fn get_end(&self) -> Position {
-        for row in 0..self.height {
-            for col in 0..self.width {
-                if self.forest[row][col] == 'E' as u8 {
-                    return Position::new(row as i32, col as i32);
-                }
-            }
-        }
-        panic!("End not present in input");
+    pub fn width(&self) -> usize {
+        self.forest.width()
     }
 
     fn init_start_end(&mut self) {
         self.start = self.get_start();
         self.end = self.get_end();
-        self.set_forest_value(self.start, 'a' as u8 - 1);
-        self.set_forest_value(self.end, 'z' as u8 + 1);
+        self.set_forest_value(self.start, b'a' - 1);
+        self.set_forest_value(self.end, b'z' + 1);
     }
 
-    fn set_forest_value(&mut self, pos: Position, value: u8) {
-        self.forest[pos.row as usize][pos.col as usize] = value;
-
+    fn set_forest_value(&mut self, pos: Point, value: u8) {
+        self.forest[pos] = value;
     }
 
-    fn get_forest_value(&self, pos: Position) -> Option<&u8> {
-        if pos.row < 0 || pos.col < 0 {
-            return None;
-        }
-
-        match self.forest.get(pos.row as usize) {
-            Some(x) => {
-                match x.get(pos.col as usize) {
-                    Some(val) => { Some(val) },
-                    None => {None}
-                }
-            }
-            None => {None}
-        }
+    fn get_forest_value(&self, pos: Point) -> u8 {
+        self.forest[pos]
     }
 
-    fn get_nodeindex(&self, pos: Position) -> NodeIndex {
-        self.nodeindices[pos.row as usize][pos.col as usize]
+    pub fn get_possible_moves(&self, pos: Point) -> Vec<Point> {
+        let current_val = self.get_forest_value(pos);
+        pos.neighbours4()
+            .filter(|x| match self.forest.at(*x) {
+                Some(val) => current_val + 1 == *val || current_val >= *val,
+                None => false,
+            })
+            .collect()
     }
+}
 
-    fn gen_graph(&mut self, pos: Position) {        
-        self.counter.insert(*(self.get_forest_value(pos)).unwrap() as char);
-        
-        if self.graph.edges_directed(self.get_nodeindex(pos), Outgoing).count() != 0 {
-            // We are looping because this was already visited
-            return;
-        }
-        
-        let moves = self.get_possible_moves(pos);
-        if moves.is_empty() {
-            return;
-        }        
-        
-        let startindex = self.get_nodeindex(pos);
-        for mov in moves {
-            // Add edge connecting the possible climb
-            let endindex = self.get_nodeindex(mov);
-            self.graph.add_edge(startindex, endindex, Climb);
-            self.gen_graph(mov);
-        }
-    }
 
-    fn get_possible_moves(&self, pos: Position) -> Vec<Position> {
-        let mut moves = vec![];
-        let directions = vec![
-            Direction::North,
-            Direction::South,
-            Direction::West,
-            Direction::East,
-        ];
-        let current_val = self.get_forest_value(pos).unwrap();
-        for direction in directions {
-            let new_pos = pos.get_position(direction);
-            match self.get_forest_value(new_pos) {
-                Some(x) => { 
-                    if current_val + 1 == *x || current_val >= x{
-                        moves.push(new_pos)
-                    }
-                },
-                None => {}
-            }
+pub fn parse_input(data: &str) -> Result<Forest> {
+    let heights = Grid::parse(data, |c| match c {
+        'a'..='z' | 'S' | 'E' => Ok(c as u8),
+        _ => Err(format!("Unexpected height '{}'", c)),
+    })?;
+    for marker in ['S', 'E'] {
+        if !data.contains(marker) {
+            return Err(AocError::at(data, &data[..0], format!("No '{}' square in the input", marker)));
         }
-        moves
     }
-}
-
 
-fn parse_input(filepath: &str) -> Forest {
-    let data: String = fs::read_to_string(filepath).expect("Cannot read file");
-    let data:Vec<Vec<_>> = data.trim().lines()
-                                .map(|x| x.chars()
-                                    .map(|y| y as u8)
-                                    .collect())
-                                .collect();
-    let mut forest = Forest::new(data.len(), data[0].len(), data);
+    let mut forest = Forest::new(heights);
     forest.init_start_end();
-    //forest.init_nodes();
-    forest.gen_graph(forest.start);
-    forest
+    // S is one of the squares part2 starts from, so both parts have an
+    // answer once this path exists
+    if bfs(forest.start, |x| forest.get_possible_moves(*x)).distance(&forest.end).is_none() {
+        let end = data.find('E').unwrap();
+        return Err(AocError::at(data, &data[end..end + 1], "No path leads from S up to E"));
+    }
+    Ok(forest)
 }
 
-pub fn part1() {
-    let forest = parse_input("./src/day12/input.txt");
-    let dot = Dot::with_config(&forest.graph, &[Config::EdgeNoLabel]);
-    //println!("{:?}", dot);
-    let path = petgraph::algo::astar(&forest.graph, 
-        forest.get_nodeindex(forest.start),
-        |finish| finish == forest.get_nodeindex(forest.end),
-        |_| 1,
-        |_| 1);
-    match path {
-        Some((_, path)) => {
-            println!("Pathlen: {}", path.len() - 1);
-            // let path:Vec<Position> = path.iter().map(|x| forest.graph[*x]).collect();
-            // let vals:Vec<char> = path.iter().map(|x| *(forest.get_forest_value(*x).unwrap()) as char).collect();
-            // println!("PATH: {:?}", path);
-            // println!("PATH: {:?}", vals);
-        },
-        None => {
-            println!("Path not found!");
-        }
-    };
-    //println!("Counter: {:?}", forest.counter);
-    //println!("ANS: {}", result);
+impl Solution for Day12 {
+    type Input = Forest;
 
-    
-}
+    fn parse(&self, data: &str) -> Result<Self::Input> {
+        parse_input(data)
+    }
 
-pub fn part2() {
-    let mut forest = parse_input("./src/day12/input.txt");
-    let dot = Dot::with_config(&forest.graph, &[Config::EdgeNoLabel]);
-    //println!("{:?}", dot);
+    fn part1(&self, forest: &Self::Input) -> Answer {
+        let search = bfs(forest.start, |x| forest.get_possible_moves(*x));
+        // parse_input made sure there is a path
+        let steps = search.distance(&forest.end).unwrap();
+        if log::enabled(log::Level::Debug) {
+            let path = search.path(&forest.end).unwrap();
+            let vals:String = path.iter().map(|x| forest.get_forest_value(*x) as char).collect();
+            debug!("Path: {:?}", path);
+            debug!("Heights: {}", vals);
+        }
+        trace!("Reached {} of {} squares", search.distances().len(), forest.height() * forest.width());
+        Answer::from(steps)
+    }
+
+    fn part2(&self, forest: &Self::Input) -> Answer {
+        let start_pos: Vec<Point> = forest.forest.iter()
+            .filter(|(_, x)| **x == b'a' - 1 || **x == b'a')
+            .map(|(pos, _)| Point::from_cell(pos))
+            .collect();
+        debug!("{} start positions", start_pos.len());
+        trace!("Start positions: {:?}", start_pos);
+        // Searching from all of them at once gives the distance to the closest
+        let search = bfs_multi(start_pos, |x| forest.get_possible_moves(*x));
+        let steps = search.distance(&forest.end).unwrap();
+        if log::enabled(log::Level::Debug) {
+            debug!("Best start: {}", search.path(&forest.end).unwrap()[0]);
+        }
+        Answer::from(steps)
+    }
 
-    let mut start_pos: Vec<Position> = vec![];
-    for row in 0..forest.height {
-        for col in 0..forest.width {
-            if forest.forest[row][col] == 'a' as u8 -1 || 
-                forest.forest[row][col] == 'a' as u8
-            {
-                start_pos.push(Position::new(row as i32, col as i32));
-            }
+    fn explore(&self, forest: &Self::Input, command: &str, _args: &[&str]) -> Option<Explored> {
+        match command {
+            "start" => Some(Ok(forest.start().to_string())),
+            "end" => Some(Ok(forest.end().to_string())),
+            "size" => Some(Ok(format!("{} rows, {} columns", forest.height(), forest.width()))),
+            _ => None,
         }
     }
-    println!("Start positions: {:?}", start_pos);
-    let mut min = usize::MAX;
-    for start in start_pos {
-        println!("Chekcing path for {:?}", start);
-        // Get rid of all edges 
-        forest.graph.clear_edges();
-        forest.counter = HashSet::new();
-        // Generate graph again with a new start point
-        forest.gen_graph(start);
-        
-        
-        let path = petgraph::algo::astar(&forest.graph, 
-            forest.get_nodeindex(start),
-            |finish| finish == forest.get_nodeindex(forest.end),
-            |_| 1,
-            |_| 1);
-        match path {
-            Some((_, path)) => {
-                let result = path.len() - 1;
-                if result < min {
-                    min = result;
-                }
-            },
-            None => {
-                println!("Path not found for {:?}", start);
-                //let dot = Dot::with_config(&forest.graph, &[Config::EdgeNoLabel]);
-                //println!("{:?}", dot);
-                //panic!("Counter: {:?}", forest.counter);
-            }
-        };
+
+    fn explore_help(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("start, end", "Position x,y of S and E, x being the column"),
+            ("size", "Height and width of the map"),
+        ]
     }
-    println!("ANS: {}", min);
 }
 
 #[cfg(test)]
@@ -302,17 +161,31 @@ mod tests {
     //use super::*;
 
     use super::*;
+    use crate::input::InputSource;
 
     #[test]
     fn test_parser() {
-        let forest = parse_input("./src/day12/sample_input.txt");
+        let forest = parse_input(&InputSource::Sample.read(12).unwrap()).unwrap();
         let moves = forest.get_possible_moves(forest.start);
-        println!("{:?}", moves);
+        assert_eq!(moves, vec![Point::new(1, 0), Point::new(0, 1)]);
+    }
+
+    #[test]
+    fn test_part1() {
+        let forest = parse_input(&InputSource::Sample.read(12).unwrap()).unwrap();
+        assert_eq!(Day12.part1(&forest), Answer::from(31));
     }
 
     #[test]
     fn test_part2() {
+        let forest = parse_input(&InputSource::Sample.read(12).unwrap()).unwrap();
+        assert_eq!(Day12.part2(&forest), Answer::from(29));
+    }
 
+    #[test]
+    fn test_no_path() {
+        let err = parse_input("Sbc\nzzE\n").unwrap_err();
+        assert_eq!(err.to_string(), "2:3: No path leads from S up to E");
     }
 
 }
\ No newline at end of file
@@ -1 +1,3 @@
-pub mod day12;
\ No newline at end of file
+pub mod day12;
+
+pub use self::day12::{Day12, Forest, parse_input};
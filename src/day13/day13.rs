@@ -1,6 +1,13 @@
-use std::{fs, cmp::Ordering};
+use std::cmp::Ordering;
 use serde_json::{Value, json};
 
+use crate::answer::Answer;
+use crate::error::{AocError, Result, parse_at};
+use crate::parse::{blocks, lines};
+use crate::solution::Solution;
+
+pub struct Day13;
+
 #[derive(PartialEq,Eq)]
 #[derive(Debug)]
 enum CompareResult {
@@ -9,33 +16,48 @@ enum CompareResult {
     EQUAL,
 }
 
-fn parse_input(filepath: &str) -> Vec<(String, String)> {
-    let data: String = fs::read_to_string(filepath).expect("Cannot read file");
-    let data:Vec<(String, String)> = data
-                    .trim()
-                    .split("\n\n")
-                    .map(|x| (x.split("\n").next().unwrap().to_owned(), x.split("\n").last().unwrap().to_owned()))
-                    .collect();
-    
-    data
+// Packets are kept as text, they are only checked to be valid JSON holding
+// nothing but integers and lists here
+fn check_packet(data: &str, packet: &str) -> Result<String> {
+    if let Err(e) = serde_json::from_str::<Value>(packet) {
+        let msg = e.to_string();
+        let msg = msg.split(" at line").next().unwrap_or_default();
+        let mut err = AocError::at(data, packet, format!("Invalid packet: {}", msg));
+        err.column += e.column().saturating_sub(1);
+        return Err(err);
+    }
+    let mut rest = packet;
+    while let Some(c) = rest.chars().next() {
+        if c.is_ascii_digit() || c == '-' {
+            let len = rest[1..].find(|x: char| !x.is_ascii_digit()).map_or(rest.len(), |x| x + 1);
+            parse_at::<i64>(data, &rest[..len])?;
+            rest = &rest[len..];
+        } else if c == '[' || c == ']' || c == ',' || c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else {
+            return Err(AocError::at(data, &rest[..c.len_utf8()], format!("Unexpected '{}', packets only hold integers and lists", c)));
+        }
+    }
+    Ok(packet.to_owned())
 }
 
-fn parse_packets(filepath: &str) -> Vec<String> {
-    let data: String = fs::read_to_string(filepath).expect("Cannot read file");
-    let data:Vec<String> = data
-                    .trim()
-                    .replace("\n\n", "\n")
-                    .split("\n")
-                    .map(|x| x.to_string())
-                    .collect();        
-    data
+pub fn parse_input(data: &str) -> Result<Vec<(String, String)>> {
+    let mut pairs = vec![];
+    for pair in blocks(data) {
+        let packets = lines(pair);
+        if packets.len() != 2 {
+            return Err(AocError::at(data, pair, format!("Expected a pair of packets, got {} lines", packets.len())));
+        }
+        pairs.push((check_packet(data, packets[0])?, check_packet(data, packets[1])?));
+    }
+    Ok(pairs)
 }
 
 fn compare(op1: &Value, op2: &Value, level:usize) -> CompareResult {
     if op1.is_i64() && op2.is_i64() {
         let op1 = op1.as_i64().unwrap();
         let op2 = op2.as_i64().unwrap();
-        //println!("{}- Compare {} vs {}", " ".repeat(level * 2), op1, op2);
+        trace!("{}- Compare {} vs {}", " ".repeat(level * 2), op1, op2);
 
         if op1 == op2 {
             return CompareResult::EQUAL;
@@ -49,7 +71,7 @@ fn compare(op1: &Value, op2: &Value, level:usize) -> CompareResult {
     if op1.is_array() && op2.is_array() {
         let op1 = op1.as_array().unwrap();
         let op2 = op2.as_array().unwrap();
-        //println!("{}- Compare {} vs {}", " ".repeat(level * 2), serde_json::to_string(op1).unwrap(), serde_json::to_string(op2).unwrap());
+        trace!("{}- Compare {} vs {}", " ".repeat(level * 2), serde_json::to_string(op1).unwrap(), serde_json::to_string(op2).unwrap());
 
         let minlen = if op1.len() <= op2.len() {op1.len()} else {op2.len()};
 
@@ -67,25 +89,25 @@ fn compare(op1: &Value, op2: &Value, level:usize) -> CompareResult {
             return CompareResult::LARGE;
         }
 
-        return CompareResult::EQUAL;
+        CompareResult::EQUAL
     } else if op1.is_array() && op2.is_i64() {
-        if op1.as_array().unwrap().len() == 0 {
-            //println!("{}- Left side ran out of items, so inputs are in the right order", " ".repeat(level * 2));
+        if op1.as_array().unwrap().is_empty() {
+            trace!("{}- Left side ran out of items, so inputs are in the right order", " ".repeat(level * 2));
             return CompareResult::SMALL;
         }
 
         let op2 = json!(vec![op2]);
-        println!("{}- Compare {} vs {}", " ".repeat(level * 2), serde_json::to_string(op1).unwrap(), op2);
-        return compare(op1, &op2, level + 1);
+        trace!("{}- Compare {} vs {}", " ".repeat(level * 2), serde_json::to_string(op1).unwrap(), op2);
+        compare(op1, &op2, level + 1)
     } else if op2.is_array() && op1.is_i64() {
-        if op2.as_array().unwrap().len() == 0 {
-            //println!("{}- Right side ran out of items, so inputs are not in the right order", " ".repeat(level * 2));
+        if op2.as_array().unwrap().is_empty() {
+            trace!("{}- Right side ran out of items, so inputs are not in the right order", " ".repeat(level * 2));
             return CompareResult::LARGE;
         }
 
         let op1 = json!(vec![op1]);
-        println!("{}- Compare {} vs {}", " ".repeat(level * 2), op1, serde_json::to_string(op2).unwrap());
-        return compare(&op1, op2, level +1);
+        trace!("{}- Compare {} vs {}", " ".repeat(level * 2), op1, serde_json::to_string(op2).unwrap());
+        compare(&op1, op2, level +1)
     } else {
         panic!("- Unexpected objects received: {:?} and {:?}", op1, op2);
     }
@@ -97,10 +119,10 @@ fn solve_one(op1:&str, op2:&str) -> CompareResult {
     let op2:Value = serde_json::from_str(op2).unwrap();
     let level = 0;
     
-    return compare(&op1, &op2, level + 1);
+    compare(&op1, &op2, level + 1)
 }
 
-fn packet_cmp(op1:&str, op2:&str) -> Ordering {
+pub fn packet_cmp(op1:&str, op2:&str) -> Ordering {
     match solve_one(op1, op2) {
         CompareResult::EQUAL => Ordering::Equal,
         CompareResult::LARGE => Ordering::Greater,
@@ -108,35 +130,39 @@ fn packet_cmp(op1:&str, op2:&str) -> Ordering {
     }
 }
 
-pub fn part1() {
-    let problems = parse_input("./src/day13/input.txt");
-    let mut result = 0;
-    for i in 0..problems.len() {
-        let (op1, op2) = &problems[i];
-        if solve_one(op1, op2) == CompareResult::SMALL {
-            result += i + 1;
-        }
+impl Solution for Day13 {
+    type Input = Vec<(String, String)>;
+
+    fn parse(&self, data: &str) -> Result<Self::Input> {
+        parse_input(data)
     }
-    println!("Result: {}", result);
-}
 
-pub fn part2() {
-    let mut packets = parse_packets("./src/day13/input.txt");
-    // Add divider packets 
-    packets.push("[[2]]".to_string());
-    packets.push("[[6]]".to_string());
+    fn part1(&self, problems: &Self::Input) -> Answer {
+        let mut result = 0;
+        for (i, (op1, op2)) in problems.iter().enumerate() {
+            if solve_one(op1, op2) == CompareResult::SMALL {
+                result += i + 1;
+            }
+        }
+        Answer::from(result)
+    }
 
-    packets.sort_by(|a, b| packet_cmp(a, b));
+    fn part2(&self, problems: &Self::Input) -> Answer {
+        let mut packets: Vec<String> = problems.iter()
+                        .flat_map(|(op1, op2)| [op1.clone(), op2.clone()])
+                        .collect();
+        // Add divider packets 
+        packets.push("[[2]]".to_string());
+        packets.push("[[6]]".to_string());
 
-    // Find the divider packets
-    let start = packets.iter().position(|x| **x == "[[2]]".to_string()).unwrap();
-    let end = packets.iter().position(|x| **x == "[[6]]".to_string()).unwrap();
+        packets.sort_by(|a, b| packet_cmp(a, b));
 
-    println!("Result :{}", (start + 1) * (end + 1));
-    
+        // Find the divider packets
+        let start = packets.iter().position(|x| *x == "[[2]]").unwrap();
+        let end = packets.iter().position(|x| *x == "[[6]]").unwrap();
 
-    // let result = do_monkey_business(monkeys, 20);
-    // println!("ANS: {}", result);
+        Answer::from((start + 1) * (end + 1))
+    }
 }
 
 #[cfg(test)]
@@ -174,6 +200,21 @@ mod tests {
         assert_eq!(result, CompareResult::LARGE);
     }
 
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_input("[1,2]\n[1.5]\n").unwrap_err();
+        assert_eq!(err.to_string(), "2:3: Unexpected '.', packets only hold integers and lists");
+
+        let err = parse_input("[[\"a\"]]\n[1]\n").unwrap_err();
+        assert_eq!(err.to_string(), "1:3: Unexpected '\"', packets only hold integers and lists");
+
+        let err = parse_input("[1]\n[99999999999999999999]\n").unwrap_err();
+        assert_eq!(err.to_string(), "2:2: Cannot parse '99999999999999999999' as i64");
+
+        assert!(parse_input("[1]\n[1,2\n").is_err());
+        assert!(parse_input("[[], -1, [2]]\n[10]\n").is_ok());
+    }
+
     #[test]
     fn test_part2() {
 
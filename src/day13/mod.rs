@@ -1 +1,3 @@
-pub mod day13;
\ No newline at end of file
+pub mod day13;
+
+pub use self::day13::{Day13, packet_cmp, parse_input};
@@ -1,5 +1,14 @@
-use std::{fs, fmt};
-use std::{thread, time::Duration};
+use std::fmt;
+
+use crate::answer::Answer;
+use crate::error::{AocError, Result, parse_at};
+use crate::geometry::{Direction, Point};
+use crate::grid::Grid;
+use crate::params::Params;
+use crate::render::{Animation, Colour, Render, Viewport};
+use crate::solution::Solution;
+
+pub struct Day14;
 
 #[derive(PartialEq,Eq)]
 #[derive(Debug)]
@@ -10,90 +19,102 @@ enum State {
     SAND,
 }
 
-#[derive(PartialEq,Eq)]
-#[derive(Clone, Copy)]
-struct Position {   
-    row: i32,   // Need i32 here as invalid position can
-    col: i32,   // be negative integers
+impl State {
+    fn symbol(&self) -> char {
+        match self {
+            State::FREE => '.',
+            State::ROCK => '#',
+            State::SAND => 'O',
+        }
+    }
 }
 
-
-impl fmt::Debug for Position {
+impl fmt::Display for State {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "({},{})", self.row, self.col)
+        write!(f, "{}", self.symbol())
     }
 }
 
-impl Position {
-    // Swapping args here as the input in this stupid format
-    fn new(col: i32, row:i32 ) -> Self { 
-        Position { row, col }
-    }
-
-    fn from_str(inp: &str) -> Self {
-        let foo:Vec<i32> = inp.split(",").map(|f| f.parse().unwrap()).collect();
-        Position::new(foo[0], foo[1])
-    }
-
-    fn _get_north(&self) -> Position {
-        Position::new(self.col, self.row - 1)
-    }
-
-    fn get_south(&self) -> Position {
-        Position::new(self.col, self.row + 1)
-    }
+const WIDTH: usize = 1000;
+const HEIGHT: usize = 500;
+const DRIPPER: Point = Point::new(500, 0);
 
-    fn get_east(&self) -> Position {
-        Position::new(self.col + 1, self.row)
-    }
+// Leaves room for the floor and for sand rolling off the sides
+fn inside(point: Point) -> bool {
+    point.y >= 0 && point.y < HEIGHT as i64 - 2 && point.x >= 1 && point.x < WIDTH as i64 - 1
+}
 
-    fn get_west(&self) -> Position {
-        Position::new(self.col -1, self.row)
+// Parses "498,4", a slice of data
+fn parse_point(data: &str, inp: &str) -> Result<Point> {
+    let (x, y) = inp.split_once(',')
+        .ok_or_else(|| AocError::at(data, inp, format!("Expected a position like 498,4, got '{}'", inp)))?;
+    let point = Point::new(parse_at(data, x)?, parse_at(data, y)?);
+    if !inside(point) {
+        return Err(AocError::at(data, inp, format!("Position {} is outside of the plane", inp)));
     }
+    Ok(point)
 }
 
-const WIDTH: usize = 1000;
-const HEIGHT: usize = 500;
-const DRIPPER:Position = Position {row: 0, col: 500};
-
-struct Plane {
-    plane: [[State;WIDTH];HEIGHT],
+#[derive(Clone)]
+pub struct Plane {
+    plane: Grid<State>,
+    dripper: Point,
     end_on_abyss: bool,
-    animate: bool,
+    // Frames per second of the animation, 0 for none
+    fps: u32,
     abyss: usize,
 }
 
 impl Plane {
-    fn new(end_on_abyss: bool, animate: bool) -> Self {
-        let plane = [[State::FREE; WIDTH]; HEIGHT];
+    pub fn new(end_on_abyss: bool, fps: u32) -> Self {
+        let plane = Grid::new(WIDTH, HEIGHT, State::FREE);
         let abyss = 0;
-        Plane { plane, abyss, end_on_abyss, animate }
+        Plane { plane, dripper: DRIPPER, abyss, end_on_abyss, fps }
     }
 
-    fn get_state(&self, position: Position) -> State {
-        self.plane[position.row as usize][position.col as usize]
+    // Where the sand comes from, (500,0) by default
+    pub fn set_dripper(&mut self, dripper: Point) {
+        if !inside(dripper) {
+            panic!("Dripper {} is outside of the plane", dripper);
+        }
+        self.dripper = dripper;
     }
 
-    fn mark_sand(&mut self, position: Position) {
-        self.plane[position.row as usize][position.col as usize] = State::SAND;
+    // The sand of simulate_drips2 piles up in a triangle from the dripper
+    // down to the floor, which has to fit into the plane
+    pub fn check_floor(&self, dripper: Point) -> std::result::Result<(), String> {
+        let floor = (self.abyss + 2) as i64;
+        if dripper.y >= floor {
+            return Err(format!("Dripper {} is not above the floor at y={}", dripper, floor));
+        }
+        let spread = floor - dripper.y;
+        if dripper.x - spread < 0 || dripper.x + spread >= WIDTH as i64 {
+            return Err(format!("Sand from {} piles up past the sides of the plane, x must be in {}..={}",
+                dripper, spread, WIDTH as i64 - 1 - spread));
+        }
+        Ok(())
+    }
+
+    fn get_state(&self, position: Point) -> State {
+        self.plane[position]
     }
 
-    fn print(&self) {
-        self.print_anchor(Position::new(480, 0), 40, 15);        
+    fn mark_sand(&mut self, position: Point) {
+        self.plane[position] = State::SAND;
     }
 
-    fn next_drip_move(&self, current: Position) -> Position {
-        let south = current.get_south();
+    fn next_drip_move(&self, current: Point) -> Point {
+        let south = current + Direction::South.step();
         if self.get_state(south) == State::FREE {
             return south;
         }
 
-        let south_west = south.get_west();
+        let south_west = south + Direction::West.step();
         if self.get_state(south_west) == State::FREE {
             return south_west;
         }
 
-        let south_east = south.get_east();
+        let south_east = south + Direction::East.step();
         if self.get_state(south_east) == State::FREE {
             return south_east;
         }
@@ -102,38 +123,43 @@ impl Plane {
         current
     }
 
-    fn drip_one(&self) -> Option<Position> {
-        let mut current = DRIPPER;
+    pub fn drip_one(&self) -> Option<Point> {
+        let mut current = self.dripper;
         loop {
             let next = self.next_drip_move(current);
             if next == current {
                 return Some(current);
             }
-            if self.end_on_abyss {
-                if next.row >= self.abyss as i32 {
-                    return None;
-                }
-            } 
-            
+            if self.end_on_abyss && next.y >= self.abyss as i64 {
+                return None;
+            }
+
             current = next;
         }
     }
 
-    fn simulate_drips2(&mut self) -> i32 {
+    pub fn simulate_drips2(&mut self) -> i32 {
         let mut drip_counter = 0;
 
         // Construct the wall at the bottom
         let row = self.abyss + 2;
         for i in 0..WIDTH {
-            self.plane[row][i] = State::ROCK;
+            self.plane[(row, i)] = State::ROCK;
         }
 
+        // The floor spans the whole plane, the sand piles up in a triangle
+        // under the dripper
+        let floor = row as i64;
+        let corner = Point::new(self.dripper.x - floor, self.dripper.y);
+        let viewport = Viewport::new(corner, Point::new(self.dripper.x + floor, floor));
+        let mut animation = Animation::new(self.fps).viewport(viewport);
+
         loop {
             let rested = self.drip_one();
             match rested {
                 None => { return drip_counter; }, 
                 Some(x) => {
-                    if x == DRIPPER {
+                    if x == self.dripper {
                         return drip_counter;
                     }
                 }
@@ -142,122 +168,157 @@ impl Plane {
             self.mark_sand(rested.unwrap());
             drip_counter += 1;
 
-            if self.animate {
-                print!("\x1B[2J");
-                self.print();
-                thread::sleep(Duration::from_millis(50));
-            }
+            animation.frame(self);
         }
     }
 
-    fn simulate_drips(&mut self) -> i32 {
+    pub fn simulate_drips(&mut self) -> i32 {
         let mut drip_counter = 0;
+        let mut animation = Animation::new(self.fps);
         loop {
             let rested = self.drip_one();
-            match rested {
-                None => { return drip_counter; }, 
-                _ => {}
-            };
+            if rested.is_none() { return drip_counter; };
 
             self.mark_sand(rested.unwrap());
             drip_counter += 1;
-
-            if self.animate {
-                print!("\x1B[2J");
-                self.print();
-                thread::sleep(Duration::from_millis(500));
+            if rested == Some(self.dripper) {
+                // Rocks around a moved dripper can hold the sand before it
+                // ever reaches the abyss
+                return drip_counter;
             }
-        }
-    }
 
-    fn print_anchor(&self, anchor: Position, width_offset:i32, height_offset:i32) {
-        for row in anchor.row..(anchor.row+height_offset) {
-            for col in anchor.col..(anchor.col+width_offset) {
-                let chr = match self.plane[row as usize][col as usize] {
-                    State::FREE => '.',
-                    State::ROCK => '#',
-                    State::SAND => 'O',
-                };
-                print!("{}", chr);
-            }
-            println!("");
+            animation.frame(self);
         }
     }
 
-    fn draw_wall(&mut self, positions: Vec<Position>) {
+    pub fn draw_wall(&mut self, positions: Vec<Point>) {
         for i in 0..positions.len()-1 {
             self.draw_line_wall(positions[i], positions[i+1]);
 
             // Check for new abyss level and set it
-            if positions[i].row as usize > self.abyss {
-                self.abyss = positions[i].row as usize;
+            if positions[i].y as usize > self.abyss {
+                self.abyss = positions[i].y as usize;
             }
 
-            if positions[i+1].row as usize > self.abyss {
-                self.abyss = positions[i+1].row as usize;
+            if positions[i+1].y as usize > self.abyss {
+                self.abyss = positions[i+1].y as usize;
             }
         }
     }
 
-    fn draw_line_wall(&mut self, start: Position, end: Position) {
-        if start.row == end.row {
-            let iterrange = if start.col < end.col {
-                start.col..=end.col
-            } else {
-                end.col..=start.col
-            };
-
-            for i in iterrange {
-                self.plane[start.row as usize][i as usize] = State::ROCK;
-            }
-        } else if start.col == end.col {
-            let iterrange = if start.row < end.row {
-                start.row..=end.row
-            } else {
-                end.row..=start.row
-            };
-
-            for i in iterrange {
-                self.plane[i as usize][start.col as usize] = State::ROCK;
-            }
-        } else {
+    fn draw_line_wall(&mut self, start: Point, end: Point) {
+        if start.x != end.x && start.y != end.y {
+            // parse_input only lets straight walls through
             panic!("Invalid wall specs received!");
         }
+        let step = (end - start).signum();
+        let mut current = start;
+        self.plane[current] = State::ROCK;
+        while current != end {
+            current += step;
+            self.plane[current] = State::ROCK;
+        }
     }
 
-    
-
 }
 
+// Drawn like in the puzzle text, with the dripper as '+'
+impl Render for Plane {
+    fn bounds(&self) -> Viewport {
+        Viewport::at(Point::default(), WIDTH, HEIGHT)
+    }
 
-fn parse_input(filepath: &str, end_on_abyss: bool) -> Plane {
-    let data: String = fs::read_to_string(filepath).expect("Cannot read file");
-    let data = data.trim().lines();
-    let data: Vec<Vec<_>> = data.map(|x| x.split(" -> ")
-                        .map(|f| Position::from_str(f))
-                        .collect())
-                    .collect();
+    fn cell(&self, point: Point) -> char {
+        match self.plane[point] {
+            State::FREE if point == self.dripper => '+',
+            state => state.symbol(),
+        }
+    }
 
-    let mut plane = Plane::new(end_on_abyss, false);
-    for positions in data {
+    fn colour(&self, cell: char) -> Colour {
+        match cell {
+            '#' => Colour::Grey,
+            'O' => Colour::Yellow,
+            '+' => Colour::Cyan,
+            _ => Colour::Plain,
+        }
+    }
+}
+
+pub fn parse_input(data: &str, end_on_abyss: bool) -> Result<Plane> {
+    let mut walls = vec![];
+    for line in data.trim().lines() {
+        let tokens:Vec<&str> = line.split(" -> ").collect();
+        let positions = tokens.iter()
+                        .map(|x| parse_point(data, x))
+                        .collect::<Result<Vec<_>>>()?;
+        for i in 1..positions.len() {
+            let (start, end) = (positions[i - 1], positions[i]);
+            if start.x != end.x && start.y != end.y {
+                return Err(AocError::at(data, tokens[i], format!("Wall from {} to {} is not horizontal or vertical", tokens[i - 1], tokens[i])));
+            }
+        }
+        walls.push(positions);
+    }
+
+    let mut plane = Plane::new(end_on_abyss, 0);
+    for positions in walls {
         plane.draw_wall(positions);
     }
-    plane
+    Ok(plane)
 }
 
 
-pub fn part1() {
-    // Get the walls 
-    let mut plane = parse_input("./src/day14/input.txt", true);
-    // Simulate sand dropping until one goes over the abyss
-    let result = plane.simulate_drips();
-    println!("Result: {}", result);
+fn dripper(params: &Params) -> Point {
+    let (x, y) = params.pair("dripper");
+    Point::new(x, y)
 }
 
-pub fn part2() {
-    let mut plane = parse_input("./src/day14/input.txt", false);
-    let result = plane.simulate_drips2();
-    println!("ANS: {}", result + 1);
+impl Solution for Day14 {
+    type Input = Plane;
+
+    fn parse(&self, data: &str) -> Result<Self::Input> {
+        parse_input(data, false)
+    }
+
+    fn part1(&self, plane: &Self::Input) -> Answer {
+        self.part1_with(plane, &self.params())
+    }
+
+    fn part2(&self, plane: &Self::Input) -> Answer {
+        self.part2_with(plane, &self.params())
+    }
+
+    fn params(&self) -> Params {
+        Params::new()
+            .declare("dripper", (DRIPPER.x, DRIPPER.y), (1..=WIDTH as i64 - 2, 0..=HEIGHT as i64 - 3), "Where the sand comes from, as x,y like in the input")
+            .declare("fps", 0, 0..=u32::MAX as i64, "Frames per second of an animation of the sand on stderr, 0 for none")
+    }
+
+    fn check_params(&self, plane: &Self::Input, part: u8, params: &Params) -> std::result::Result<(), String> {
+        if part == 2 {
+            plane.check_floor(dripper(params))?;
+        }
+        Ok(())
+    }
+
+    fn part1_with(&self, plane: &Self::Input, params: &Params) -> Answer {
+        let mut plane = plane.clone();
+        plane.set_dripper(dripper(params));
+        plane.fps = params.int("fps");
+        plane.end_on_abyss = true;
+        // Simulate sand dropping until one goes over the abyss
+        let result = plane.simulate_drips();
+        Answer::from(result)
+    }
+
+    fn part2_with(&self, plane: &Self::Input, params: &Params) -> Answer {
+        let mut plane = plane.clone();
+        plane.set_dripper(dripper(params));
+        plane.fps = params.int("fps");
+        let result = plane.simulate_drips2();
+        Answer::from(result + 1)
+    }
 }
 
 #[cfg(test)]
@@ -267,96 +328,125 @@ mod tests {
     //use super::*;
 
     use super::*;
+    use crate::render;
 
-    fn assert_wall(plane: &Plane, wall: &Vec<Position>) {
-        for row in 0..HEIGHT {
-            for col in 0..WIDTH {
-                let curr_pos = Position::new(col as i32, row as i32);
-                if wall.contains(&curr_pos) {
-                    assert_eq!(plane.plane[row][col], State::ROCK);    
-                } else {
-                    assert_eq!(plane.plane[row][col], State::FREE);
-                }
+    fn assert_wall(plane: &Plane, wall: &[Point]) {
+        for (cell, state) in plane.plane.iter() {
+            let curr_pos = Point::from_cell(cell);
+            if wall.contains(&curr_pos) {
+                assert_eq!(*state, State::ROCK);    
+            } else {
+                assert_eq!(*state, State::FREE);
             }
         }
     }
 
     #[test]
     fn draw_wall_test() {
-        println!("Starting test..");
-        let mut plane = Plane::new(true, true);
-        println!("Does this even work?");
+        let mut plane = Plane::new(true, 0);
         let wall = vec![
-            Position::new(498, 4),
-            Position::new(498, 6),
-            Position::new(496, 6)];
+            Point::new(498, 4),
+            Point::new(498, 6),
+            Point::new(496, 6)];
         plane.draw_wall(wall);
 
         let positions = [(498, 4), (498, 5), (498, 6), (497, 6), (496, 6)];
-        let mut positions:Vec<Position> = positions.iter().map(|x| Position::new(x.0, x.1)).collect();
+        let mut positions:Vec<Point> = positions.iter().map(|x| Point::new(x.0, x.1)).collect();
         assert_wall(&plane, &positions);
         
         let wall = vec![
-            Position::new(503, 4),
-            Position::new(502, 4),
-            Position::new(502, 9),
-            Position::new(494, 9)];
+            Point::new(503, 4),
+            Point::new(502, 4),
+            Point::new(502, 9),
+            Point::new(494, 9)];
         plane.draw_wall(wall);
 
         let pos2 = [(503, 4), (502, 4), (502, 5), (502, 6), (502, 7), (502, 8), (502, 9), 
                                       (501, 9), (500, 9), (499, 9), (498, 9), (497, 9), (496, 9), (495, 9), (494, 9)];
-        let pos2:Vec<Position> = pos2.iter().map(|x| Position::new(x.0, x.1)).collect();
+        let pos2:Vec<Point> = pos2.iter().map(|x| Point::new(x.0, x.1)).collect();
         positions.extend(pos2);
-        println!("{:?}", positions);
-        plane.print();
         assert_wall(&plane, &positions);
+        // Same as the picture in the puzzle text
+        assert_eq!(render::draw_cropped(&plane), vec![
+            "......+...",
+            "..........",
+            "..........",
+            "..........",
+            "....#...##",
+            "....#...#.",
+            "..###...#.",
+            "........#.",
+            "........#.",
+            "#########.",
+        ]);
         assert_eq!(plane.abyss, 9);
 
         let rested = plane.drip_one().unwrap();
         plane.mark_sand(rested);
-        assert_eq!(rested, Position::new(500, 8));
+        assert_eq!(rested, Point::new(500, 8));
 
         let rested = plane.drip_one().unwrap();
         plane.mark_sand(rested);
-        assert_eq!(rested, Position::new(499, 8));
+        assert_eq!(rested, Point::new(499, 8));
 
         let rested = plane.drip_one().unwrap();
         plane.mark_sand(rested);
-        assert_eq!(rested, Position::new(501, 8));
+        assert_eq!(rested, Point::new(501, 8));
 
         let rested = plane.drip_one().unwrap();
         plane.mark_sand(rested);
-        assert_eq!(rested, Position::new(500, 7));
+        assert_eq!(rested, Point::new(500, 7));
 
         let rested = plane.drip_one().unwrap();
         plane.mark_sand(rested);
-        assert_eq!(rested, Position::new(498, 8));
+        assert_eq!(rested, Point::new(498, 8));
     }
 
     #[test]
     fn full_simulate_test() {
-        let mut plane = Plane::new(true, true);
+        let mut plane = Plane::new(true, 0);
         let wall = vec![
-            Position::new(498, 4),
-            Position::new(498, 6),
-            Position::new(496, 6)];
+            Point::new(498, 4),
+            Point::new(498, 6),
+            Point::new(496, 6)];
         plane.draw_wall(wall);        
         
         let wall = vec![
-            Position::new(503, 4),
-            Position::new(502, 4),
-            Position::new(502, 9),
-            Position::new(494, 9)];
+            Point::new(503, 4),
+            Point::new(502, 4),
+            Point::new(502, 9),
+            Point::new(494, 9)];
         plane.draw_wall(wall);
 
         assert_eq!(plane.abyss, 9);
 
-        let drips = plane.simulate_drips();
-        println!("DRIPS: {}", drips);
+        assert_eq!(plane.simulate_drips(), 24);
+    }
+
+    #[test]
+    fn test_check_floor() {
+        let plane = parse_input("498,4 -> 498,6 -> 496,6\n503,4 -> 502,4 -> 502,9 -> 494,9\n", false).unwrap();
+        assert_eq!(plane.check_floor(DRIPPER), Ok(()));
+        assert_eq!(plane.check_floor(Point::new(11, 0)), Ok(()));
+        assert_eq!(plane.check_floor(Point::new(10, 0)),
+            Err("Sand from 10,0 piles up past the sides of the plane, x must be in 11..=988".to_string()));
+        assert_eq!(plane.check_floor(Point::new(500, 11)), Err("Dripper 500,11 is not above the floor at y=11".to_string()));
     }
 
     #[test]
     fn operation_test() {
 
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_input("498,4 -> 498,6\n503,4 -> 502,4 -> 500,9\n", false).err().unwrap();
+        assert_eq!(err.to_string(), "2:19: Wall from 502,4 to 500,9 is not horizontal or vertical");
+
+        let err = parse_input("498,4 -> 498,x\n", false).err().unwrap();
+        assert_eq!(err.to_string(), "1:14: Cannot parse 'x' as i64");
+
+        let err = parse_input("498,4 -> 498,600\n", false).err().unwrap();
+        assert_eq!(err.to_string(), "1:10: Position 498,600 is outside of the plane");
+    }
+}
@@ -1 +1,3 @@
-pub mod day14;
\ No newline at end of file
+pub mod day14;
+
+pub use self::day14::{Day14, Plane, parse_input};
@@ -0,0 +1,132 @@
+use std::{fmt, str::FromStr};
+
+// A problem in the puzzle input. Line and column are 1-based. Parsers
+// only see the data, so the file is filled in later by the runner.
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct AocError {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+pub type Result<T> = std::result::Result<T, AocError>;
+
+// Byte offset of token inside data. Tokens are normally slices of data;
+// copies are located by searching for them instead.
+fn offset(data: &str, token: &str) -> usize {
+    let start = data.as_ptr() as usize;
+    let pos = token.as_ptr() as usize;
+    if pos >= start && pos + token.len() <= start + data.len() {
+        pos - start
+    } else {
+        data.find(token).unwrap_or(0)
+    }
+}
+
+impl AocError {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        AocError { file: None, line, column, message: message.into() }
+    }
+
+    // Error pointing at token, which should be a slice of data
+    pub fn at(data: &str, token: &str, message: impl Into<String>) -> Self {
+        let before = &data[..offset(data, token)];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|x| x + 1).unwrap_or(0);
+        let column = before[line_start..].chars().count() + 1;
+        AocError::new(line, column, message)
+    }
+
+    pub fn with_file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    // Multi line report quoting the offending line of data:
+    //
+    //     error: Unexpected direction 'X'
+    //      --> src/day09/input.txt:3:1
+    //       |
+    //     3 | X 3
+    //       | ^
+    pub fn diagnostic(&self, data: &str) -> String {
+        let mut out = format!("error: {}\n", self.message);
+        let location = format!("{}:{}", self.line, self.column);
+        let gutter = " ".repeat(self.line.to_string().len());
+        match &self.file {
+            Some(file) => out += &format!("{}--> {}:{}\n", gutter, file, location),
+            None => out += &format!("{}--> {}\n", gutter, location),
+        }
+        if let Some(text) = self.line.checked_sub(1).and_then(|x| data.lines().nth(x)) {
+            out += &format!("{} |\n", gutter);
+            out += &format!("{} | {}\n", self.line, text);
+            out += &format!("{} | {}^\n", gutter, " ".repeat(self.column.saturating_sub(1)));
+        }
+        out
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file)?;
+        }
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for AocError {}
+
+// Parses token, a slice of data, reporting its position when it fails
+pub fn parse_at<T: FromStr>(data: &str, token: &str) -> Result<T> {
+    token.parse::<T>().map_err(|_| {
+        AocError::at(data, token, format!("Cannot parse '{}' as {}", token, std::any::type_name::<T>()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &str = "R 4\nU 4\nX 3\n";
+
+    #[test]
+    fn test_position() {
+        let line = DATA.lines().nth(2).unwrap();
+        let err = AocError::at(DATA, &line[2..], "Bad distance");
+        assert_eq!((err.line, err.column), (3, 3));
+
+        // Copies are found by searching
+        let err = AocError::at(DATA, "U 4", "Bad line");
+        assert_eq!((err.line, err.column), (2, 1));
+
+        let err = AocError::at(DATA, &DATA[..0], "Empty");
+        assert_eq!((err.line, err.column), (1, 1));
+    }
+
+    #[test]
+    fn test_parse_at() {
+        let line = DATA.lines().nth(1).unwrap();
+        assert_eq!(parse_at::<u32>(DATA, &line[2..]), Ok(4));
+
+        let err = parse_at::<u32>(DATA, &line[..1]).unwrap_err();
+        assert_eq!(err.to_string(), "2:1: Cannot parse 'U' as u32");
+    }
+
+    #[test]
+    fn test_diagnostic() {
+        let line = DATA.lines().nth(2).unwrap();
+        let err = AocError::at(DATA, &line[..1], "Unexpected direction 'X'").with_file("input.txt");
+        assert_eq!(err.to_string(), "input.txt:3:1: Unexpected direction 'X'");
+        assert_eq!(err.diagnostic(DATA), "\
+error: Unexpected direction 'X'
+ --> input.txt:3:1
+  |
+3 | X 3
+  | ^
+");
+    }
+}
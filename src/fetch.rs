@@ -0,0 +1,83 @@
+use std::{fs, path::Path};
+
+use crate::client::{Client, ClientError};
+
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+pub enum Fetched {
+    // The file was there already, nothing was requested
+    Cached,
+    Downloaded(usize),
+}
+
+// Downloads the input of day to path, unless it was downloaded before.
+// Inputs never change, so there is no point in asking the site again.
+pub fn fetch_input(client: &Client, day: u8, path: &Path) -> Result<Fetched, String> {
+    let cached = fs::metadata(path).map(|x| x.len() > 0).unwrap_or(false);
+    if cached {
+        return Ok(Fetched::Cached);
+    }
+
+    let data = client.get(&format!("/day/{}/input", day)).map_err(|e| match e {
+        ClientError::NotFound(url) => format!("Day {} has no input at {}", day, url),
+        e => format!("Cannot fetch day {}: {}", day, e),
+    })?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+    }
+    fs::write(path, &data).map_err(|e| format!("Cannot write {}: {}", path.display(), e))?;
+    Ok(Fetched::Downloaded(data.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+    use crate::config::Config;
+    use crate::mock_server::serve;
+
+    fn client(url: &str, session: Option<&str>) -> Client {
+        Client::new(Config { session: session.map(|x| x.to_string()), base_url: url.to_string() })
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        env::temp_dir().join(format!("aoc_fetch_{}_{}", process::id(), name)).join("input.txt")
+    }
+
+    #[test]
+    fn test_download_and_cache() {
+        let server = serve(vec![(200, "1000\n2000\n")]);
+        let client = client(&server.url, Some("abc"));
+        let path = temp_path("cache");
+
+        assert_eq!(fetch_input(&client, 1, &path), Ok(Fetched::Downloaded(10)));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1000\n2000\n");
+        // Served its only response, a second request would not be answered
+        assert_eq!(fetch_input(&client, 1, &path), Ok(Fetched::Cached));
+
+        let requests = server.requests();
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        assert!(requests[0].starts_with("GET /2022/day/1/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("session=abc"));
+    }
+
+    #[test]
+    fn test_errors() {
+        let path = temp_path("errors");
+        let err = fetch_input(&client("http://127.0.0.1:1", None), 3, &path).unwrap_err();
+        assert!(err.starts_with("Cannot fetch day 3: No session token"));
+
+        let server = serve(vec![
+            (404, "Please don't repeatedly request this endpoint before it unlocks!"),
+            (404, "404 Not Found"),
+            (400, "Puzzle inputs differ by user.  Please log in to get your puzzle input."),
+        ]);
+        let client = client(&server.url, Some("abc"));
+        assert_eq!(fetch_input(&client, 25, &path).unwrap_err(), "Cannot fetch day 25: The puzzle is not unlocked yet");
+        assert_eq!(fetch_input(&client, 26, &path).unwrap_err(), format!("Day 26 has no input at {}/2022/day/26/input", server.url));
+        assert!(fetch_input(&client, 3, &path).unwrap_err().contains("session token was rejected"));
+        server.requests();
+        assert!(!path.exists());
+    }
+}
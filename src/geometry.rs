@@ -0,0 +1,222 @@
+use std::{fmt, ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign}, str::FromStr};
+
+// A point or a vector on the plane. y grows downwards, like the rows of
+// the puzzle inputs, so north is y - 1.
+#[derive(PartialEq,Eq)]
+#[derive(Hash)]
+#[derive(PartialOrd,Ord)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(Default)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const fn new(x: i64, y: i64) -> Self {
+        Point { x, y }
+    }
+
+    // Point of the (row, col) cell of a Grid
+    pub fn from_cell((row, col): (usize, usize)) -> Self {
+        Point::new(col as i64, row as i64)
+    }
+
+    pub fn manhattan(&self, other: Point) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    // Distance when diagonal steps count as one, like a king in chess
+    pub fn chebyshev(&self, other: Point) -> i64 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    // Each coordinate as -1, 0 or 1. Adding the signum of a - b to b takes
+    // a single, possibly diagonal, step from b towards a.
+    pub fn signum(&self) -> Point {
+        Point::new(self.x.signum(), self.y.signum())
+    }
+
+    pub fn neighbours4(&self) -> impl Iterator<Item = Point> + '_ {
+        Direction::ALL.iter().map(move |x| *self + x.step())
+    }
+
+    pub fn neighbours8(&self) -> impl Iterator<Item = Point> + '_ {
+        (-1..=1)
+            .flat_map(|y| (-1..=1).map(move |x| Point::new(x, y)))
+            .filter(|x| *x != Point::default())
+            .map(move |x| *self + x)
+    }
+}
+
+// Written like the coordinates in the inputs
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: i64) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+#[derive(PartialEq,Eq)]
+#[derive(Hash)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    // Clockwise, starting north
+    pub const ALL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    // Unit vector pointing this way
+    pub fn step(&self) -> Point {
+        match self {
+            Direction::North => Point::new(0, -1),
+            Direction::East => Point::new(1, 0),
+            Direction::South => Point::new(0, 1),
+            Direction::West => Point::new(-1, 0),
+        }
+    }
+
+    pub fn turn_right(&self) -> Direction {
+        Direction::ALL[(*self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(&self) -> Direction {
+        Direction::ALL[(*self as usize + 3) % 4]
+    }
+
+    pub fn opposite(&self) -> Direction {
+        Direction::ALL[(*self as usize + 2) % 4]
+    }
+
+    // U/D/L/R as well as N/S/E/W, in either case
+    pub fn from_char(c: char) -> Option<Direction> {
+        match c.to_ascii_uppercase() {
+            'U' | 'N' => Some(Direction::North),
+            'R' | 'E' => Some(Direction::East),
+            'D' | 'S' => Some(Direction::South),
+            'L' | 'W' => Some(Direction::West),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next().and_then(Direction::from_char), chars.next()) {
+            (Some(direction), None) => Ok(direction),
+            _ => Err(format!("Unexpected direction '{}'", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point::new(3, -2);
+        let b = Point::new(-1, 4);
+        assert_eq!(a + b, Point::new(2, 2));
+        assert_eq!(a - b, Point::new(4, -6));
+        assert_eq!(-a, Point::new(-3, 2));
+        assert_eq!(a * 3, Point::new(9, -6));
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+        assert_eq!(a.to_string(), "3,-2");
+        assert_eq!(Point::from_cell((2, 5)), Point::new(5, 2));
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point::new(1, 1);
+        let b = Point::new(4, -1);
+        assert_eq!(a.manhattan(b), 5);
+        assert_eq!(a.chebyshev(b), 3);
+        assert_eq!((b - a).signum(), Point::new(1, -1));
+        assert_eq!(Point::default().signum(), Point::default());
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let p = Point::new(5, 5);
+        let around: Vec<Point> = p.neighbours4().collect();
+        assert_eq!(around, vec![Point::new(5, 4), Point::new(6, 5), Point::new(5, 6), Point::new(4, 5)]);
+        assert_eq!(p.neighbours8().count(), 8);
+        assert!(p.neighbours8().all(|x| x.chebyshev(p) == 1));
+    }
+
+    #[test]
+    fn test_direction() {
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction::West.turn_right(), Direction::North);
+        assert_eq!(Direction::East.opposite(), Direction::West);
+        for direction in Direction::ALL {
+            assert_eq!(direction.step(), -direction.opposite().step());
+            assert_eq!(direction.turn_left().turn_right(), direction);
+        }
+        assert_eq!(Direction::from_char('U'), Some(Direction::North));
+        assert_eq!(Direction::from_char('w'), Some(Direction::West));
+        assert_eq!(Direction::from_char('X'), None);
+        assert_eq!("R".parse::<Direction>(), Ok(Direction::East));
+        assert_eq!("S".parse::<Direction>(), Ok(Direction::South));
+        assert_eq!("RR".parse::<Direction>(), Err("Unexpected direction 'RR'".to_string()));
+        assert!("".parse::<Direction>().is_err());
+    }
+}
@@ -0,0 +1,290 @@
+use std::{fmt, ops::{Index, IndexMut}};
+
+use crate::error::{AocError, Result};
+use crate::geometry::Point;
+use crate::parse::lines;
+
+// Steps to the 4 orthogonal neighbours: north, south, west, east
+pub const STEPS4: [(i64, i64); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+// Steps to the 8 surrounding cells, in reading order
+pub const STEPS8: [(i64, i64); 8] = [
+    (-1, -1), (-1, 0), (-1, 1),
+    (0, -1),           (0, 1),
+    (1, -1),  (1, 0),  (1, 1),
+];
+
+// A 2-D map of cells stored row by row in a single Vec. Positions are
+// (row, col) with (0, 0) in the top left corner, like the puzzle inputs.
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T: Clone> Grid<T> {
+    // Every cell set to value
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Grid { cells: vec![value; width * height], width, height }
+    }
+}
+
+impl<T> Grid<T> {
+    // Panics unless all rows have the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map(|x| x.len()).unwrap_or(0);
+        if let Some(row) = rows.iter().find(|x| x.len() != width) {
+            panic!("Expected {} cells in every row, got {}", width, row.len());
+        }
+        Grid { cells: rows.into_iter().flatten().collect(), width, height }
+    }
+
+    // One cell per char, one row per line of data. cell turns a char into
+    // a cell or tells what is wrong with it.
+    pub fn parse<F>(data: &str, mut cell: F) -> Result<Self>
+    where
+        F: FnMut(char) -> std::result::Result<T, String>,
+    {
+        let lines = lines(data);
+        let width = lines.first().map(|x| x.chars().count()).unwrap_or(0);
+        let mut cells = vec![];
+        for line in &lines {
+            for (i, c) in line.char_indices() {
+                cells.push(cell(c).map_err(|e| AocError::at(data, &line[i..], e))?);
+            }
+            let count = line.chars().count();
+            if count != width {
+                return Err(AocError::at(data, line, format!("Expected {} cells in every row, got {}", width, count)));
+            }
+        }
+        Ok(Grid { cells, width, height: lines.len() })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, row: i64, col: i64) -> bool {
+        row >= 0 && col >= 0 && (row as usize) < self.height && (col as usize) < self.width
+    }
+
+    // Checked access, None outside of the grid
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        if row < self.height && col < self.width {
+            Some(&self.cells[row * self.width + col])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        if row < self.height && col < self.width {
+            Some(&mut self.cells[row * self.width + col])
+        } else {
+            None
+        }
+    }
+
+    // Checked access by point, x being the column and y the row
+    pub fn at(&self, point: Point) -> Option<&T> {
+        if self.contains(point.y, point.x) {
+            self.get(point.y as usize, point.x as usize)
+        } else {
+            None
+        }
+    }
+
+    // Position one step away from (row, col), if still inside
+    pub fn step(&self, (row, col): (usize, usize), (drow, dcol): (i64, i64)) -> Option<(usize, usize)> {
+        let (row, col) = (row as i64 + drow, col as i64 + dcol);
+        if self.contains(row, col) {
+            Some((row as usize, col as usize))
+        } else {
+            None
+        }
+    }
+
+    pub fn neighbours4(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        STEPS4.iter().filter_map(move |x| self.step((row, col), *x))
+    }
+
+    pub fn neighbours8(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        STEPS8.iter().filter_map(move |x| self.step((row, col), *x))
+    }
+
+    // Positions from (row, col) going in steps until the edge, without
+    // (row, col) itself
+    pub fn ray(&self, row: usize, col: usize, step: (i64, i64)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let mut current = (row, col);
+        std::iter::from_fn(move || {
+            current = self.step(current, step)?;
+            Some(current)
+        })
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on 0, an empty grid has no rows anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.width, "Column {} is outside of a grid {} wide", col, self.width);
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    // Every position in reading order
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| (i / width, i % width))
+    }
+
+    // (position, cell) in reading order
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    // First position in reading order whose cell matches
+    pub fn position<P: Fn(&T) -> bool>(&self, predicate: P) -> Option<(usize, usize)> {
+        self.iter().find(|(_, x)| predicate(x)).map(|(pos, _)| pos)
+    }
+}
+
+// Unchecked access, panics outside of the grid
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        match self.get(row, col) {
+            Some(x) => x,
+            None => panic!("({},{}) is outside of a {}x{} grid", row, col, self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(row, col) {
+            Some(x) => x,
+            None => panic!("({},{}) is outside of a {}x{} grid", row, col, width, height),
+        }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        match self.at(point) {
+            Some(x) => x,
+            None => panic!("{} is outside of a {}x{} grid", point, self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        if !self.contains(point.y, point.x) {
+            panic!("{} is outside of a {}x{} grid", point, self.width, self.height);
+        }
+        &mut self[(point.y as usize, point.x as usize)]
+    }
+}
+
+// Cells side by side, one line per row
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(data: &str) -> Result<Grid<u32>> {
+        Grid::parse(data, |c| c.to_digit(10).ok_or(format!("Unexpected '{}'", c)))
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits("123\n456\n").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 0)], 4);
+        assert_eq!(grid.get(1, 3), None);
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.to_string(), "123\n456\n");
+        assert_eq!(grid, Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]));
+
+        assert_eq!(digits("123\n4x6\n").unwrap_err().to_string(), "2:2: Unexpected 'x'");
+        assert_eq!(digits("123\n45\n").unwrap_err().to_string(), "2:1: Expected 3 cells in every row, got 2");
+    }
+
+    #[test]
+    fn test_access() {
+        let mut grid = Grid::new(3, 2, '.');
+        grid[(1, 2)] = '#';
+        *grid.get_mut(0, 0).unwrap() = 'O';
+        assert_eq!(grid.to_string(), "O..\n..#\n");
+        assert!(grid.get_mut(0, 3).is_none());
+        assert!(grid.contains(1, 2));
+        assert!(!grid.contains(-1, 0));
+        assert!(!grid.contains(2, 0));
+        assert_eq!(grid.position(|x| *x == '#'), Some((1, 2)));
+        assert_eq!(grid.position(|x| *x == 'x'), None);
+
+        assert_eq!(grid[Point::new(2, 1)], '#');
+        assert_eq!(grid.at(Point::new(-1, 0)), None);
+        assert_eq!(grid.at(Point::new(0, 2)), None);
+        grid[Point::new(1, 0)] = 'x';
+        assert_eq!(grid.row(0), &['O', 'x', '.']);
+    }
+
+    #[test]
+    #[should_panic(expected = "(0,3) is outside of a 3x2 grid")]
+    fn test_index_outside() {
+        // Would be cell (1,0) without the column check
+        let _ = Grid::new(3, 2, 0)[(0, 3)];
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = digits("123\n456\n789\n").unwrap();
+        let around = |pos: Vec<(usize, usize)>| -> Vec<u32> { pos.iter().map(|x| grid[*x]).collect() };
+        assert_eq!(around(grid.neighbours4(1, 1).collect()), vec![2, 8, 4, 6]);
+        assert_eq!(around(grid.neighbours4(0, 0).collect()), vec![4, 2]);
+        assert_eq!(around(grid.neighbours8(1, 1).collect()), vec![1, 2, 3, 4, 6, 7, 8, 9]);
+        assert_eq!(around(grid.neighbours8(2, 2).collect()), vec![5, 6, 8]);
+    }
+
+    #[test]
+    fn test_lines() {
+        let grid = digits("123\n456\n789\n").unwrap();
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![3, 6, 9]);
+        assert_eq!(grid.rows().count(), 3);
+        assert_eq!(grid.ray(2, 0, (-1, 1)).collect::<Vec<_>>(), vec![(1, 1), (0, 2)]);
+        assert_eq!(grid.ray(1, 1, (0, -1)).collect::<Vec<_>>(), vec![(1, 0)]);
+        assert_eq!(grid.ray(0, 0, (-1, 0)).count(), 0);
+        assert_eq!(grid.positions().nth(4), Some((1, 1)));
+        assert_eq!(grid.iter().map(|(_, x)| x).sum::<u32>(), 45);
+    }
+}
@@ -0,0 +1,94 @@
+use std::{fs, io::{self, Read}, path::PathBuf};
+
+// Where the puzzle input of a day comes from
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum InputSource {
+    // src/dayNN/input.txt
+    Default,
+    // src/dayNN/sample_input.txt
+    Sample,
+    Path(PathBuf),
+    Stdin,
+}
+
+// Resolved against the crate and not the current working directory, so
+// the binary can be run from anywhere
+pub fn day_dir(day: u8) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join(format!("day{:02}", day))
+}
+
+impl InputSource {
+    pub fn path(&self, day: u8) -> Option<PathBuf> {
+        match self {
+            InputSource::Default => Some(day_dir(day).join("input.txt")),
+            InputSource::Sample => Some(day_dir(day).join("sample_input.txt")),
+            InputSource::Path(path) => Some(path.clone()),
+            InputSource::Stdin => None,
+        }
+    }
+
+    // Human readable name of the input, used when reporting results
+    pub fn name(&self, day: u8) -> String {
+        match self.path(day) {
+            Some(path) => path.display().to_string(),
+            None => "<stdin>".to_string(),
+        }
+    }
+
+    // Short name of the input which does not depend on the checkout, like
+    // "input" or "sample", so that it can be stored
+    pub fn label(&self) -> String {
+        match self {
+            InputSource::Default => "input".to_string(),
+            InputSource::Sample => "sample".to_string(),
+            InputSource::Path(path) => path.display().to_string(),
+            InputSource::Stdin => "<stdin>".to_string(),
+        }
+    }
+
+    pub fn read(&self, day: u8) -> io::Result<String> {
+        match self.path(day) {
+            Some(path) => fs::read_to_string(path),
+            None => {
+                let mut data = String::new();
+                io::stdin().read_to_string(&mut data)?;
+                Ok(data)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths() {
+        let path = InputSource::Default.path(7).unwrap();
+        assert!(path.is_absolute());
+        assert!(path.ends_with("src/day07/input.txt"));
+
+        let path = InputSource::Sample.path(12).unwrap();
+        assert!(path.ends_with("src/day12/sample_input.txt"));
+
+        let path = InputSource::Path(PathBuf::from("foo.txt")).path(3).unwrap();
+        assert_eq!(path, PathBuf::from("foo.txt"));
+
+        assert_eq!(InputSource::Stdin.path(1), None);
+        assert_eq!(InputSource::Stdin.name(1), "<stdin>");
+
+        assert_eq!(InputSource::Default.label(), "input");
+        assert_eq!(InputSource::Sample.label(), "sample");
+        assert_eq!(InputSource::Path(PathBuf::from("foo.txt")).label(), "foo.txt");
+    }
+
+    #[test]
+    fn test_read() {
+        let data = InputSource::Sample.read(8).unwrap();
+        assert_eq!(data.lines().next(), Some("30373"));
+    }
+}
@@ -0,0 +1,261 @@
+use std::{fmt, ops::{Add, Sub}};
+
+// Integers an interval can be made of. TryFrom<u8> gives the 0 and the 1
+// needed to count and to step past an end, every integer type has it.
+pub trait Bound: Copy + Ord + fmt::Debug + Add<Output = Self> + Sub<Output = Self> + TryFrom<u8> {}
+
+impl<T> Bound for T where T: Copy + Ord + fmt::Debug + Add<Output = T> + Sub<Output = T> + TryFrom<u8> {}
+
+fn int<T: Bound>(value: u8) -> T {
+    T::try_from(value).unwrap_or_else(|_| panic!("{} does not fit the bound type", value))
+}
+
+// Both ends included, like the "2-4" ranges of the inputs. Never empty,
+// which is why the ends can only be set through new.
+#[derive(PartialEq,Eq)]
+#[derive(Hash)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T: Bound> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        if start > end {
+            panic!("Interval starts at {:?}, after its end {:?}", start, end);
+        }
+        Interval { start, end }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    // Number of values in it
+    pub fn length(&self) -> T {
+        self.end - self.start + int(1)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    // Whether other lies entirely inside
+    pub fn covers(&self, other: Interval<T>) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: Interval<T>) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: Interval<T>) -> Option<Interval<T>> {
+        if !self.overlaps(other) {
+            return None;
+        }
+        Some(Interval::new(self.start.max(other.start), self.end.min(other.end)))
+    }
+
+    // What is left once other is taken out: nothing, one piece or two
+    pub fn difference(&self, other: Interval<T>) -> Vec<Interval<T>> {
+        if !self.overlaps(other) {
+            return vec![*self];
+        }
+        let mut pieces = vec![];
+        if self.start < other.start {
+            pieces.push(Interval::new(self.start, other.start - int(1)));
+        }
+        if other.end < self.end {
+            pieces.push(Interval::new(other.end + int(1), self.end));
+        }
+        pieces
+    }
+
+    // Overlapping or right next to each other, so one interval covers both
+    fn touches(&self, other: Interval<T>) -> bool {
+        let (first, second) = if self.start <= other.start { (self, &other) } else { (&other, self) };
+        second.start <= first.end || second.start - int(1) == first.end
+    }
+}
+
+// Written like the ranges in the inputs
+impl<T: fmt::Display> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+// Union of intervals, kept as sorted ranges that neither overlap nor touch
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Interval<T>>,
+}
+
+impl<T: Bound> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { ranges: vec![] }
+    }
+
+    pub fn ranges(&self) -> &[Interval<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let mut merged = interval;
+        let mut ranges = Vec::with_capacity(self.ranges.len() + 1);
+        for range in &self.ranges {
+            if range.touches(merged) {
+                merged = Interval::new(range.start.min(merged.start), range.end.max(merged.end));
+            } else {
+                ranges.push(*range);
+            }
+        }
+        ranges.push(merged);
+        ranges.sort_by_key(|x| x.start);
+        self.ranges = ranges;
+    }
+
+    pub fn remove(&mut self, interval: Interval<T>) {
+        self.ranges = self.ranges.iter().flat_map(|x| x.difference(interval)).collect();
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut union = self.clone();
+        for range in &other.ranges {
+            union.insert(*range);
+        }
+        union
+    }
+
+    pub fn subtract(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut difference = self.clone();
+        for range in &other.ranges {
+            difference.remove(*range);
+        }
+        difference
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.ranges.iter().any(|x| x.contains(value))
+    }
+
+    // Number of values in the set
+    pub fn coverage(&self) -> T {
+        self.ranges.iter().fold(int(0), |acc, x| acc + x.length())
+    }
+
+    // Values missing between the first and the last range
+    pub fn gaps(&self) -> impl Iterator<Item = Interval<T>> + '_ {
+        self.ranges.windows(2).map(|x| Interval::new(x[0].end + int(1), x[1].start - int(1)))
+    }
+}
+
+impl<T: Bound> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet::new()
+    }
+}
+
+impl<T: Bound> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iv(start: i64, end: i64) -> Interval<i64> {
+        Interval::new(start, end)
+    }
+
+    #[test]
+    fn test_interval() {
+        let a = iv(2, 6);
+        assert_eq!(a.length(), 5);
+        assert_eq!(iv(4, 4).length(), 1);
+        assert!(a.contains(2) && a.contains(6) && !a.contains(7));
+        assert!(a.covers(iv(3, 6)));
+        assert!(!a.covers(iv(3, 7)));
+        assert!(a.overlaps(iv(6, 8)));
+        assert!(!a.overlaps(iv(7, 8)));
+        assert_eq!(a.intersection(iv(4, 8)), Some(iv(4, 6)));
+        assert_eq!(a.intersection(iv(-3, 1)), None);
+        assert_eq!(a.to_string(), "2-6");
+    }
+
+    #[test]
+    fn test_difference() {
+        let a = iv(2, 6);
+        assert_eq!(a.difference(iv(3, 4)), vec![iv(2, 2), iv(5, 6)]);
+        assert_eq!(a.difference(iv(0, 3)), vec![iv(4, 6)]);
+        assert_eq!(a.difference(iv(5, 9)), vec![iv(2, 4)]);
+        assert_eq!(a.difference(iv(7, 9)), vec![a]);
+        assert!(a.difference(iv(1, 6)).is_empty());
+    }
+
+    #[test]
+    fn test_bound_types() {
+        let a: Interval<i8> = Interval::new(-3, 3);
+        assert_eq!((a.start(), a.end()), (-3, 3));
+        assert_eq!(a.length(), 7);
+        assert_eq!(a.difference(Interval::new(0, 0)), vec![Interval::new(-3, -1), Interval::new(1, 3)]);
+
+        let set: IntervalSet<u8> = [Interval::new(0, 4), Interval::new(5, 9)].into_iter().collect();
+        assert_eq!(set.ranges(), &[Interval::new(0, 9)]);
+        assert_eq!(set.coverage(), 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Interval starts at 4, after its end 2")]
+    fn test_backwards() {
+        iv(4, 2);
+    }
+
+    #[test]
+    fn test_set() {
+        let mut set: IntervalSet<i64> = [iv(10, 14), iv(1, 3), iv(16, 20)].into_iter().collect();
+        assert_eq!(set.ranges(), &[iv(1, 3), iv(10, 14), iv(16, 20)]);
+        assert_eq!(set.coverage(), 13);
+        assert_eq!(set.gaps().collect::<Vec<_>>(), vec![iv(4, 9), iv(15, 15)]);
+
+        // Touching ranges are merged too
+        set.insert(iv(15, 15));
+        set.insert(iv(4, 5));
+        assert_eq!(set.ranges(), &[iv(1, 5), iv(10, 20)]);
+        assert!(set.contains(12) && !set.contains(7));
+
+        set.remove(iv(3, 11));
+        assert_eq!(set.ranges(), &[iv(1, 2), iv(12, 20)]);
+        set.remove(iv(0, 30));
+        assert!(set.is_empty());
+        assert_eq!(set.coverage(), 0);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: IntervalSet<i64> = [iv(0, 5), iv(10, 15)].into_iter().collect();
+        let b: IntervalSet<i64> = [iv(4, 11), iv(20, 20)].into_iter().collect();
+        assert_eq!(a.union(&b).ranges(), &[iv(0, 15), iv(20, 20)]);
+        assert_eq!(a.subtract(&b).ranges(), &[iv(0, 3), iv(12, 15)]);
+        assert_eq!(b.subtract(&a).ranges(), &[iv(6, 9), iv(20, 20)]);
+        assert_eq!(a.union(&b).gaps().collect::<Vec<_>>(), vec![iv(16, 19)]);
+    }
+}
@@ -0,0 +1,56 @@
+#![allow(clippy::module_inception, clippy::upper_case_acronyms)]
+
+// Advent of Code 2022. Every day lives in its own dayNN module and
+// implements solution::Solution; registry() lists all of them. The aoc
+// binary is a command line runner on top of this.
+
+pub mod answer;
+pub mod bench;
+pub mod cli;
+pub mod client;
+pub mod config;
+pub mod error;
+pub mod fetch;
+pub mod geometry;
+pub mod grid;
+pub mod input;
+pub mod interval;
+#[macro_use]
+pub mod log;
+pub mod memory;
+#[cfg(test)]
+mod mock_server;
+pub mod params;
+pub mod parse;
+pub mod pool;
+pub mod render;
+pub mod repl;
+pub mod runner;
+pub mod scaffold;
+pub mod search;
+#[macro_use]
+pub mod solution;
+pub mod submit;
+pub mod verify;
+pub mod watch;
+
+pub use answer::Answer;
+pub use error::{AocError, Result};
+pub use solution::{Day, Solution};
+
+days! {
+    1 => day01::Day01,
+    2 => day02::Day02,
+    3 => day03::Day03,
+    4 => day04::Day04,
+    5 => day05::Day05,
+    6 => day06::Day06,
+    7 => day07::Day07,
+    8 => day08::Day08,
+    9 => day09::Day09,
+    10 => day10::Day10,
+    11 => day11::Day11,
+    12 => day12::Day12,
+    13 => day13::Day13,
+    14 => day14::Day14,
+}
@@ -0,0 +1,50 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+// How chatty the solvers are, set once from -v/-vv. Everything goes to
+// stderr so that answers and --format json on stdout stay clean.
+#[derive(PartialEq,Eq,PartialOrd,Ord)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Level {
+    Quiet,
+    // -v: a few lines per day, like intermediate results
+    Debug,
+    // -vv: everything, like every step of a simulation
+    Trace,
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn level() -> Level {
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => Level::Quiet,
+        1 => Level::Debug,
+        _ => Level::Trace,
+    }
+}
+
+pub fn enabled(level: Level) -> bool {
+    self::level() >= level
+}
+
+// Only evaluates its arguments when -v is given
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Debug) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+// Only evaluates its arguments when -vv is given
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Trace) {
+            eprintln!($($arg)*);
+        }
+    };
+}
@@ -1,51 +1,240 @@
+use std::{env, process};
 
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
+use aoc::{bench, cli, fetch, log, memory, registry, repl, runner, scaffold, submit, verify, watch};
+use aoc::cli::{Command, Format, Options, Target};
+use aoc::client::Client;
+use aoc::config::Config;
+use aoc::answer::Answer;
+use aoc::error::AocError;
+use aoc::input::InputSource;
+use aoc::params::Params;
+use aoc::solution::Day;
+use aoc::verify::Answers;
+
+fn read_input(day: &Day, source: &InputSource) -> String {
+    match source.read(day.day) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Cannot read {}: {}", source.name(day.day), e);
+            process::exit(1);
+        }
+    }
+}
+
+// Prints where the input is broken and exits
+fn report_parse_error(day: &Day, source: &InputSource, data: &str, err: AocError) -> ! {
+    eprint!("{}", err.with_file(source.name(day.day)).diagnostic(data));
+    process::exit(1);
+}
+
+// The day's parameters with the --param overrides, exits when one is wrong
+fn day_params(day: &Day, overrides: &[(String, String)]) -> Params {
+    match day.params(overrides) {
+        Ok(params) => params,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
+
+// Parses the input of the day once and runs all the selected parts on it
+fn run(day: &Day, targets: &[Target], source: &InputSource, params: &Params) {
+    let data = read_input(day, source);
+    let input = match day.solver.parse_dyn(&data) {
+        Ok(input) => input,
+        Err(e) => report_parse_error(day, source, &data, e),
+    };
+    for target in targets {
+        let (answer, stats) = memory::measure(|| day.solver.solve_dyn(&input, target.part, params));
+        let answer = answer.unwrap_or_else(|e| {
+            eprintln!("{}: {}", target, e);
+            process::exit(1);
+        });
+        println!("{}: {}", target, answer);
+        if let Some(stats) = stats {
+            println!("{}: {}", target, stats);
+        }
+    }
+}
+
+fn run_all(registry: &[Day], options: &Options) {
+    for day in registry {
+        let selected: Vec<Target> = options.targets.iter().filter(|x| x.day == day.day).cloned().collect();
+        if !selected.is_empty() {
+            run(day, &selected, &options.input, &day_params(day, &options.params));
+        }
+    }
+}
+
+fn run_parallel(registry: &[Day], options: &Options) {
+    let rows = runner::run_parallel(registry, &options.targets, &options.input, &options.params);
+    match options.format {
+        Format::Text => runner::print_table(&rows),
+        Format::Json => println!("{}", serde_json::to_string_pretty(&runner::to_json(&rows)).unwrap()),
+    }
+    if rows.iter().any(|x| x.error.is_some()) {
+        process::exit(1);
+    }
+}
+
+fn run_bench(registry: &[Day], options: &Options, runs: usize) {
+    let mut results = vec![];
+    for day in registry {
+        let parts: Vec<u8> = options.targets.iter().filter(|x| x.day == day.day).map(|x| x.part).collect();
+        if !parts.is_empty() {
+            let data = read_input(day, &options.input);
+            let params = day_params(day, &options.params);
+            let parsed = match day.solver.parse_dyn(&data) {
+                Ok(parsed) => parsed,
+                Err(e) => report_parse_error(day, &options.input, &data, e),
+            };
+            for part in &parts {
+                if let Err(e) = day.solver.check_dyn(&parsed, *part, &params) {
+                    eprintln!("{}: {}", Target::new(day.day, *part), e);
+                    process::exit(1);
+                }
+            }
+            results.extend(bench::bench_day(day, &data, &options.input.label(), &parsed, &parts, &params, runs));
+        }
+    }
+
+    let path = bench::baseline_path();
+    let baseline = if path.exists() {
+        bench::load_baseline(&path).unwrap_or_else(|e| {
+            eprintln!("Ignoring baseline: {}", e);
+            vec![]
+        })
+    } else {
+        vec![]
+    };
+    bench::print_table(&results, &baseline);
+
+    if options.save_baseline {
+        if let Err(e) = bench::save_baseline(&path, &bench::merge(&baseline, &results)) {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+        println!("Baseline saved to {}", path.display());
+    }
+}
+
+fn run_verify(registry: &[Day], days: &[u8]) {
+    let answers = match Answers::load_all(days) {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
+    let rows = verify::verify(registry, days, &answers);
+    verify::print_table(&rows);
+    if !verify::all_ok(&rows) {
+        process::exit(1);
+    }
+}
+
+fn run_fetch(day: u8) {
+    let path = InputSource::Default.path(day).unwrap();
+    let result = Config::load().and_then(|config| fetch::fetch_input(&Client::new(config), day, &path));
+    match result {
+        Ok(fetch::Fetched::Cached) => println!("{} is already there", path.display()),
+        Ok(fetch::Fetched::Downloaded(size)) => println!("Saved {} bytes to {}", size, path.display()),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_submit(registry: &[Day], target: Target) {
+    let day = registry.iter().find(|x| x.day == target.day).unwrap();
+    let source = InputSource::Default;
+    let data = read_input(day, &source);
+    let input = match day.solver.parse_dyn(&data) {
+        Ok(input) => input,
+        Err(e) => report_parse_error(day, &source, &data, e),
+    };
+    // The site only knows the answer to the puzzle as asked
+    let answer = match day.solver.solve_dyn(&input, target.part, &day.solver.params_dyn()) {
+        Err(e) => {
+            eprintln!("{}: {}", target, e);
+            process::exit(1);
+        },
+        Ok(Answer::Grid(rows)) => {
+            eprintln!("{} draws its answer, read it and submit it on the site:\n{}", target, rows.join("\n"));
+            process::exit(1);
+        },
+        Ok(answer) => answer.canonical(),
+    };
+    println!("{}: submitting {}", target, answer);
+
+    let result = Config::load()
+        .and_then(|config| submit::submit(&Client::new(config), &submit::history_path(), target.day, target.part, &answer));
+    match result {
+        Ok(attempt) => {
+            println!("{}: {}", attempt.verdict.name(), attempt.message);
+            if attempt.verdict != submit::Verdict::Correct {
+                process::exit(1);
+            }
+        },
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_new(day: u8) {
+    match scaffold::new_day(day) {
+        Ok(created) => {
+            for path in created {
+                println!("Created {}", path.display());
+            }
+            println!("Registered day {} in src/lib.rs", day);
+        },
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
 
 fn main() {
-    let mode = String::from("d14p2");
-    match &mode[..] {
-        "d1p1" => day01::day01::part1(),
-        "d1p2" => day01::day01::part2(),
-        "d2p1" => day02::day02::part1(),
-        "d2p2" => day02::day02::part2(),
-        "d3p1" => day03::day03::part1(),
-        "d3p2" => day03::day03::part2(),
-        "d4p1" => day04::day04::part1(),
-        "d4p2" => day04::day04::part2(),
-        "d5p1" => day05::day05::part1(),
-        "d5p2" => day05::day05::part2(),
-        "d6p1" => day06::day06::part1(),
-        "d6p2" => day06::day06::part2(),
-        "d7p1" => day07::day07::part1(),
-        "d7p2" => day07::day07::part2(),
-        "d8p1" => day08::day08::part1(),
-        "d8p2" => day08::day08::part2(),
-        "d9p1" => day09::day09::part1(),
-        "d9p2" => day09::day09::part2(),
-        "d10p1" => day10::day10::part1(),
-        "d10p2" => day10::day10::part2(),
-        "d11p1" => day11::day11::part1(),
-        "d11p2" => day11::day11::part2(),
-        "d12p1" => day12::day12::part1(),
-        "d12p2" => day12::day12::part2(),
-        "d13p1" => day13::day13::part1(),
-        "d13p2" => day13::day13::part2(),
-        "d14p1" => day14::day14::part1(),
-        "d14p2" => day14::day14::part2(),
-
-        _ => println!("Invalid mode!"),
-    }
-}
\ No newline at end of file
+    let registry = registry();
+    let days: Vec<u8> = registry.iter().map(|x| x.day).collect();
+
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    log::set_level(cli::take_verbosity(&mut args));
+    let command = match cli::parse_args(&args, &days) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}", e);
+            let days: Vec<String> = days.iter().map(|x| x.to_string()).collect();
+            eprintln!("Valid days: {}", days.join(", "));
+            eprintln!("{}", cli::USAGE);
+            process::exit(2);
+        }
+    };
+
+    match command {
+        Command::Run(options) => match options.bench {
+            Some(runs) => run_bench(&registry, &options, runs),
+            None if options.all || options.format == Format::Json => run_parallel(&registry, &options),
+            None => run_all(&registry, &options),
+        },
+        Command::Verify(days) => run_verify(&registry, &days),
+        Command::Watch(options) => watch::watch(&options),
+        Command::Fetch(day) => run_fetch(day),
+        Command::Submit(target) => run_submit(&registry, target),
+        Command::New(day) => run_new(day),
+        Command::Repl(options) => {
+            let day = registry.iter().find(|x| x.day == options.targets[0].day).unwrap();
+            let params = day_params(day, &options.params);
+            if let Err(e) = repl::repl(day, options.input, params) {
+                eprintln!("{}", e.trim_end());
+                process::exit(1);
+            }
+        },
+    }
+}
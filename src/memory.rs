@@ -0,0 +1,176 @@
+use std::{alloc::{GlobalAlloc, Layout, System}, cell::Cell, fmt};
+
+// What a piece of code allocated, see measure
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub struct AllocStats {
+    // Calls to alloc and realloc
+    pub count: u64,
+    // Sum of the sizes asked for, freed or not
+    pub bytes: u64,
+    // Most memory held at once on top of what was held before
+    pub peak: u64,
+}
+
+impl fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} allocs, {} total, {} peak", self.count, fmt_bytes(self.bytes), fmt_bytes(self.peak))
+    }
+}
+
+pub fn fmt_bytes(bytes: u64) -> String {
+    let bytes = bytes as f64;
+    if bytes < 1024.0 {
+        format!("{}B", bytes)
+    } else if bytes < 1024.0 * 1024.0 {
+        format!("{:.1}KB", bytes / 1024.0)
+    } else if bytes < 1024.0 * 1024.0 * 1024.0 {
+        format!("{:.1}MB", bytes / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1}GB", bytes / (1024.0 * 1024.0 * 1024.0))
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Counters {
+    count: u64,
+    bytes: u64,
+    // Can go below zero on a thread freeing what another one allocated
+    current: i64,
+    peak: i64,
+}
+
+thread_local! {
+    // Per thread, so that days running in parallel do not count each
+    // other's allocations. Cell of a Copy type, so no destructor to run
+    // and nothing allocated when the allocator touches it.
+    static COUNTERS: Cell<Counters> = const { Cell::new(Counters { count: 0, bytes: 0, current: 0, peak: 0 }) };
+}
+
+fn record(allocated: usize, freed: usize) {
+    // Fails while the thread is torn down, those allocations are not ours
+    let _ = COUNTERS.try_with(|counters| {
+        let mut x = counters.get();
+        if allocated > 0 {
+            x.count += 1;
+            x.bytes += allocated as u64;
+        }
+        x.current += allocated as i64 - freed as i64;
+        x.peak = x.peak.max(x.current);
+        counters.set(x);
+    });
+}
+
+// The system allocator, counting on the side. Installed as the global
+// allocator by the alloc-stats feature.
+pub struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(0, layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            record(new_size, layout.size());
+        }
+        new
+    }
+}
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+pub fn enabled() -> bool {
+    cfg!(feature = "alloc-stats")
+}
+
+// Runs f and returns what it allocated on this thread, or None when built
+// without the alloc-stats feature. Can be nested.
+pub fn measure<R, F: FnOnce() -> R>(f: F) -> (R, Option<AllocStats>) {
+    if !enabled() {
+        return (f(), None);
+    }
+    let start = COUNTERS.with(|counters| {
+        let start = counters.get();
+        counters.set(Counters { peak: start.current, ..start });
+        start
+    });
+    let result = f();
+    let end = COUNTERS.with(|counters| {
+        let end = counters.get();
+        // Keep the peak of an enclosing measure
+        counters.set(Counters { peak: end.peak.max(start.peak), ..end });
+        end
+    });
+    let stats = AllocStats {
+        count: end.count - start.count,
+        bytes: end.bytes - start.bytes,
+        peak: (end.peak - start.current).max(0) as u64,
+    };
+    (result, Some(stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fmt_bytes() {
+        assert_eq!(fmt_bytes(512), "512B");
+        assert_eq!(fmt_bytes(1536), "1.5KB");
+        assert_eq!(fmt_bytes(3 * 1024 * 1024), "3.0MB");
+        let stats = AllocStats { count: 3, bytes: 2048, peak: 100 };
+        assert_eq!(stats.to_string(), "3 allocs, 2.0KB total, 100B peak");
+    }
+
+    #[test]
+    #[cfg(not(feature = "alloc-stats"))]
+    fn test_disabled() {
+        assert_eq!(measure(|| vec![1u8; 100].len()), (100, None));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc-stats")]
+    fn test_measure() {
+        let (len, outer) = measure(|| {
+            let (_, inner) = measure(|| vec![0u64; 1000].len());
+            let inner = inner.unwrap();
+            assert_eq!(inner.count, 1);
+            assert_eq!(inner.bytes, 8000);
+            assert_eq!(inner.peak, 8000);
+
+            let mut small = vec![];
+            for i in 0..100u64 {
+                small.push(i);
+            }
+            small.len()
+        });
+        let outer = outer.unwrap();
+        assert_eq!(len, 100);
+        // The freed 8000 bytes still count towards the peak
+        assert!(outer.peak >= 8000);
+        assert!(outer.count > 2);
+        assert!(outer.bytes > 8000);
+    }
+}
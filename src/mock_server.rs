@@ -0,0 +1,53 @@
+use std::{io::{BufRead, BufReader, Read, Write}, net::TcpListener, thread::{self, JoinHandle}};
+
+// Stand-in for the Advent of Code site in tests. Every connection gets the
+// next canned (status, body) response; the requests received, head and
+// body, are handed back by requests().
+pub struct MockServer {
+    pub url: String,
+    handle: JoinHandle<Vec<String>>,
+}
+
+pub fn serve(responses: Vec<(u16, &str)>) -> MockServer {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let responses: Vec<(u16, String)> = responses.into_iter().map(|(x, y)| (x, y.to_string())).collect();
+
+    let handle = thread::spawn(move || {
+        let mut requests = vec![];
+        for (status, body) in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+                request += &line;
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+            }
+            let mut content = vec![0; length];
+            reader.read_exact(&mut content).unwrap();
+            request += &String::from_utf8_lossy(&content);
+            requests.push(request);
+
+            write!(stream, "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body).unwrap();
+        }
+        requests
+    });
+    MockServer { url, handle }
+}
+
+impl MockServer {
+    // Waits until every canned response was sent
+    pub fn requests(self) -> Vec<String> {
+        self.handle.join().unwrap()
+    }
+}
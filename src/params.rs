@@ -0,0 +1,230 @@
+use std::{collections::BTreeMap, fmt, ops::RangeInclusive};
+
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum ParamValue {
+    Int(i64),
+    // Written as "500,0", like the coordinates in the inputs
+    Pair(i64, i64),
+}
+
+impl fmt::Display for ParamValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParamValue::Int(x) => write!(f, "{}", x),
+            ParamValue::Pair(x, y) => write!(f, "{},{}", x, y),
+        }
+    }
+}
+
+impl From<i64> for ParamValue {
+    fn from(value: i64) -> Self {
+        ParamValue::Int(value)
+    }
+}
+
+impl From<(i64, i64)> for ParamValue {
+    fn from(value: (i64, i64)) -> Self {
+        ParamValue::Pair(value.0, value.1)
+    }
+}
+
+impl ParamValue {
+    // Parses text as the same kind of value as self
+    fn parse_like(&self, text: &str) -> Result<ParamValue, String> {
+        let int = |x: &str| x.trim().parse::<i64>().ok();
+        let parsed = match self {
+            ParamValue::Int(_) => int(text).map(ParamValue::Int),
+            ParamValue::Pair(..) => text.split_once(',')
+                .and_then(|(x, y)| Some(ParamValue::Pair(int(x)?, int(y)?))),
+        };
+        parsed.ok_or_else(|| match self {
+            ParamValue::Int(_) => format!("Expected an integer, got '{}'", text),
+            ParamValue::Pair(..) => format!("Expected a pair like 500,0, got '{}'", text),
+        })
+    }
+}
+
+// Values a parameter accepts, so that the parts never see one they
+// cannot work with
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum ParamRange {
+    Int(RangeInclusive<i64>),
+    // x and y each have their own range
+    Pair(RangeInclusive<i64>, RangeInclusive<i64>),
+}
+
+impl From<RangeInclusive<i64>> for ParamRange {
+    fn from(value: RangeInclusive<i64>) -> Self {
+        ParamRange::Int(value)
+    }
+}
+
+impl From<(RangeInclusive<i64>, RangeInclusive<i64>)> for ParamRange {
+    fn from(value: (RangeInclusive<i64>, RangeInclusive<i64>)) -> Self {
+        ParamRange::Pair(value.0, value.1)
+    }
+}
+
+impl ParamRange {
+    fn check(&self, value: ParamValue) -> Result<(), String> {
+        let within = |what: &str, x: i64, range: &RangeInclusive<i64>| {
+            if range.contains(&x) {
+                Ok(())
+            } else {
+                Err(format!("{}{} is not in {}..={}", what, x, range.start(), range.end()))
+            }
+        };
+        match (self, value) {
+            (ParamRange::Int(range), ParamValue::Int(x)) => within("", x, range),
+            (ParamRange::Pair(xs, ys), ParamValue::Pair(x, y)) => {
+                within("x = ", x, xs)?;
+                within("y = ", y, ys)
+            },
+            _ => Err(format!("{} does not match the range {:?}", value, self)),
+        }
+    }
+}
+
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone)]
+struct Param {
+    value: ParamValue,
+    range: ParamRange,
+    help: &'static str,
+}
+
+// The tunable numbers of a day. A solver declares every parameter with
+// its default and the values it accepts, --param overrides them and the
+// parts read them back:
+//
+//     Params::new().declare("rounds", 20, 0..=u32::MAX as i64, "Rounds of monkey business")
+//     ...
+//     let rounds: u64 = params.int("rounds");
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Default)]
+pub struct Params {
+    params: BTreeMap<&'static str, Param>,
+}
+
+impl Params {
+    pub fn new() -> Self {
+        Params::default()
+    }
+
+    pub fn declare(mut self, name: &'static str, default: impl Into<ParamValue>, range: impl Into<ParamRange>, help: &'static str) -> Self {
+        let (value, range) = (default.into(), range.into());
+        if let Err(e) = range.check(value) {
+            panic!("Default of parameter {}: {}", name, e);
+        }
+        self.params.insert(name, Param { value, range, help });
+        self
+    }
+
+    // Overrides a declared parameter, text must be of the declared type
+    // and within the declared range
+    pub fn set(&mut self, name: &str, text: &str) -> Result<(), String> {
+        let Some(param) = self.params.get_mut(name) else {
+            let known: Vec<&str> = self.params.keys().cloned().collect();
+            if known.is_empty() {
+                return Err(format!("Unknown parameter '{}', this day has none", name));
+            }
+            return Err(format!("Unknown parameter '{}', expected one of {}", name, known.join(", ")));
+        };
+        let value = param.value.parse_like(text)
+            .and_then(|value| param.range.check(value).map(|_| value))
+            .map_err(|e| format!("{}: {}", name, e))?;
+        param.value = value;
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> ParamValue {
+        match self.params.get(name) {
+            Some(param) => param.value,
+            None => panic!("Parameter {} is not declared", name),
+        }
+    }
+
+    // Integer parameter, converted to what the part works with. The
+    // declared range has to fit into T.
+    pub fn int<T: TryFrom<i64>>(&self, name: &str) -> T {
+        match self.get(name) {
+            ParamValue::Int(x) => T::try_from(x)
+                .unwrap_or_else(|_| panic!("Parameter {} = {} is out of range", name, x)),
+            x => panic!("Parameter {} = {} is not an integer", name, x),
+        }
+    }
+
+    pub fn pair(&self, name: &str) -> (i64, i64) {
+        match self.get(name) {
+            ParamValue::Pair(x, y) => (x, y),
+            x => panic!("Parameter {} = {} is not a pair", name, x),
+        }
+    }
+
+    // (name, value, help) of every parameter, sorted by name
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, ParamValue, &'static str)> + '_ {
+        self.params.iter().map(|(name, param)| (*name, param.value, param.help))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> Params {
+        Params::new()
+            .declare("rounds", 20, 0..=u32::MAX as i64, "Rounds to play")
+            .declare("dripper", (500, 0), (1..=998, 0..=497), "Where the sand comes from")
+    }
+
+    #[test]
+    fn test_defaults() {
+        let params = params();
+        assert_eq!(params.int::<u64>("rounds"), 20);
+        assert_eq!(params.pair("dripper"), (500, 0));
+        let names: Vec<&str> = params.iter().map(|x| x.0).collect();
+        assert_eq!(names, vec!["dripper", "rounds"]);
+    }
+
+    #[test]
+    fn test_set() {
+        let mut params = params();
+        params.set("rounds", "10000").unwrap();
+        params.set("dripper", "490, 3").unwrap();
+        assert_eq!(params.int::<usize>("rounds"), 10000);
+        assert_eq!(params.get("dripper").to_string(), "490,3");
+
+        assert_eq!(params.set("round", "1"), Err("Unknown parameter 'round', expected one of dripper, rounds".to_string()));
+        assert_eq!(params.set("rounds", "x"), Err("rounds: Expected an integer, got 'x'".to_string()));
+        assert!(params.set("dripper", "490").is_err());
+        assert!(Params::new().set("rounds", "1").unwrap_err().contains("has none"));
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let mut params = params();
+        assert_eq!(params.set("rounds", "-1"), Err("rounds: -1 is not in 0..=4294967295".to_string()));
+        assert_eq!(params.set("dripper", "0,0"), Err("dripper: x = 0 is not in 1..=998".to_string()));
+        assert_eq!(params.set("dripper", "500,498"), Err("dripper: y = 498 is not in 0..=497".to_string()));
+        // Rejected values leave the old one in place
+        assert_eq!(params.int::<u64>("rounds"), 20);
+        assert_eq!(params.pair("dripper"), (500, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Default of parameter rounds: 20 is not in 0..=10")]
+    fn test_default_out_of_range() {
+        Params::new().declare("rounds", 20, 0..=10, "Rounds to play");
+    }
+}
@@ -0,0 +1,166 @@
+use std::str::FromStr;
+
+use regex::Regex;
+
+use crate::error::{AocError, Result, parse_at};
+use crate::grid::Grid;
+
+// Everything here hands out slices of data, so that errors about them
+// still point at the right line and column. Line endings can be \n or
+// \r\n and trailing whitespace is dropped.
+
+// Lines of data, without blank lines at the start and at the end. Leading
+// whitespace is kept, some inputs line things up with it.
+pub fn lines(data: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = data.lines().map(|x| x.trim_end()).collect();
+    while lines.last().is_some_and(|x| x.is_empty()) {
+        lines.pop();
+    }
+    let start = lines.iter().position(|x| !x.is_empty()).unwrap_or(lines.len());
+    lines.drain(..start);
+    lines
+}
+
+// Groups of lines separated by one or more blank lines
+pub fn blocks(data: &str) -> Vec<&str> {
+    let mut blocks = vec![];
+    // Start and end of the block being read
+    let mut current: Option<(usize, usize)> = None;
+    for line in data.lines().map(|x| x.trim_end()) {
+        let start = line.as_ptr() as usize - data.as_ptr() as usize;
+        if line.is_empty() {
+            if let Some((first, last)) = current.take() {
+                blocks.push(&data[first..last]);
+            }
+        } else {
+            let first = current.map(|(x, _)| x).unwrap_or(start);
+            current = Some((first, start + line.len()));
+        }
+    }
+    if let Some((first, last)) = current {
+        blocks.push(&data[first..last]);
+    }
+    blocks
+}
+
+// One cell per char, see Grid::parse
+pub fn grid<T, F>(data: &str, cell: F) -> Result<Grid<T>>
+where
+    F: FnMut(char) -> std::result::Result<T, String>,
+{
+    Grid::parse(data, cell)
+}
+
+// Integers in line, including their sign. A '-' right after a letter or a
+// digit is not a sign, so "2-4" gives 2 and 4 while "x=-4" gives -4.
+fn int_tokens(line: &str) -> Vec<&str> {
+    let bytes = line.as_bytes();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        let sign = bytes[i] == b'-'
+            && bytes.get(i + 1).is_some_and(|x| x.is_ascii_digit())
+            && (i == 0 || !bytes[i - 1].is_ascii_alphanumeric());
+        if !sign && !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        tokens.push(&line[start..i]);
+    }
+    tokens
+}
+
+// Every integer in line, a slice of data, as T
+pub fn ints<T: FromStr>(data: &str, line: &str) -> Result<Vec<T>> {
+    int_tokens(line).into_iter().map(|x| parse_at(data, x)).collect()
+}
+
+// Groups of a regex matched against a line of data, parsed on demand
+pub struct Captures<'a> {
+    data: &'a str,
+    line: &'a str,
+    captures: regex::Captures<'a>,
+}
+
+impl<'a> Captures<'a> {
+    // Text of group i, empty if it did not take part in the match
+    pub fn text(&self, i: usize) -> &'a str {
+        self.captures.get(i).map(|x| x.as_str()).unwrap_or(&self.line[..0])
+    }
+
+    pub fn get<T: FromStr>(&self, i: usize) -> Result<T> {
+        parse_at(self.data, self.text(i))
+    }
+
+    pub fn name<T: FromStr>(&self, name: &str) -> Result<T> {
+        let text = self.captures.name(name).map(|x| x.as_str()).unwrap_or(&self.line[..0]);
+        parse_at(self.data, text)
+    }
+}
+
+// Matches re against line, a slice of data. expected tells what the line
+// should look like when it does not match.
+pub fn captures<'a>(data: &'a str, re: &Regex, line: &'a str, expected: &str) -> Result<Captures<'a>> {
+    let captures = re.captures(line)
+        .ok_or_else(|| AocError::at(data, line, format!("Expected '{}'", expected)))?;
+    Ok(Captures { data, line, captures })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(lines("\n  a \r\nb\r\n\r\nc\n\n"), vec!["  a", "b", "", "c"]);
+        assert_eq!(lines("a\nb"), vec!["a", "b"]);
+        assert!(lines("\r\n\n").is_empty());
+
+        // Errors point into data
+        let data = "1\r\n2\r\nx\r\n";
+        let err = lines(data).iter().map(|x| parse_at::<u32>(data, x)).collect::<Result<Vec<_>>>().unwrap_err();
+        assert_eq!(err.to_string(), "3:1: Cannot parse 'x' as u32");
+    }
+
+    #[test]
+    fn test_blocks() {
+        let data = "1\r\n2\r\n\r\n3\r\n \r\n\r\n    [D]\r\n4\r\n";
+        assert_eq!(blocks(data), vec!["1\r\n2", "3", "    [D]\r\n4"]);
+        assert_eq!(lines(blocks(data)[0]), vec!["1", "2"]);
+        assert_eq!(blocks("\n\na\n"), vec!["a"]);
+        assert!(blocks("").is_empty());
+    }
+
+    #[test]
+    fn test_ints() {
+        let data = "Sensor at x=-2, y=15: 2-4,6-8 x3\r\n";
+        assert_eq!(ints::<i64>(data, data).unwrap(), vec![-2, 15, 2, 4, 6, 8, 3]);
+        assert_eq!(ints::<i64>(data, "- -1 a-1 --2").unwrap(), vec![-1, 1, -2]);
+        assert!(ints::<i64>(data, "no numbers").unwrap().is_empty());
+
+        let data = "1, -2\n";
+        assert_eq!(ints::<u32>(data, data).unwrap_err().to_string(), "1:4: Cannot parse '-2' as u32");
+    }
+
+    #[test]
+    fn test_captures() {
+        let re = Regex::new(r"move (\d+) from (?P<from>\d+) to (\d+)( now)?").unwrap();
+        let data = "move 3 from 1 to 2\r\nmove 1 from 2\r\n";
+        let lines = lines(data);
+
+        let caps = captures(data, &re, lines[0], "move <n> from <stack> to <stack>").unwrap();
+        assert_eq!(caps.get::<u32>(1), Ok(3));
+        assert_eq!(caps.name::<usize>("from"), Ok(1));
+        assert_eq!(caps.text(3), "2");
+        assert_eq!(caps.text(4), "");
+        assert!(caps.get::<u32>(4).is_err());
+
+        let err = captures(data, &re, lines[1], "move <n> from <stack> to <stack>").err().unwrap();
+        assert_eq!(err.to_string(), "2:1: Expected 'move <n> from <stack> to <stack>'");
+    }
+}
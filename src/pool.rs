@@ -0,0 +1,119 @@
+use std::{any::Any, cell::Cell, panic::{self, AssertUnwindSafe}, sync::{Mutex, Once, atomic::{AtomicUsize, Ordering}}, thread};
+
+// Solvers are free to recurse deeply, so give the workers as much stack
+// as the main thread gets
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+// Runs f on every job using a fixed number of worker threads. Results are
+// returned in the same order as the jobs.
+pub fn run_parallel<J, R, F>(jobs: &[J], f: F) -> Vec<R>
+where
+    J: Sync,
+    R: Send,
+    F: Fn(&J) -> R + Sync,
+{
+    let workers = thread::available_parallelism()
+        .map(|x| x.get())
+        .unwrap_or(1)
+        .min(jobs.len());
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(jobs.iter().map(|_| None).collect());
+
+    thread::scope(|s| {
+        for _ in 0..workers {
+            thread::Builder::new()
+                .stack_size(STACK_SIZE)
+                .spawn_scoped(s, || loop {
+                    let i = next.fetch_add(1, Ordering::SeqCst);
+                    if i >= jobs.len() {
+                        break;
+                    }
+                    let result = f(&jobs[i]);
+                    results.lock().unwrap()[i] = Some(result);
+                })
+                .expect("Cannot spawn worker thread");
+        }
+    });
+
+    results.into_inner().unwrap()
+        .into_iter()
+        .map(|x| x.expect("Worker thread died before finishing its job"))
+        .collect()
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "panicked".to_string()
+    }
+}
+
+thread_local! {
+    // Set while catch runs on this thread
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+// The panic hook is global, so it is wrapped once for the whole process
+// instead of being swapped around each catch. Panics which catch reports
+// itself are not printed, the others still go to the previous hook.
+fn install_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CATCHING.with(|x| x.get()) {
+                hook(info);
+            }
+        }));
+    });
+}
+
+// Turns a panic inside f into an error carrying the panic message, which
+// is then not printed to stderr
+pub fn catch<R, F: FnOnce() -> R>(f: F) -> Result<R, String> {
+    install_hook();
+    let outer = CATCHING.with(|x| x.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|x| x.set(outer));
+    result.map_err(|e| panic_message(e.as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_parallel() {
+        let jobs: Vec<u64> = (0..50).collect();
+        let results = run_parallel(&jobs, |x| x * x);
+        assert_eq!(results, jobs.iter().map(|x| x * x).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn test_catch() {
+        assert_eq!(catch(|| 5), Ok(5));
+
+        let jobs = vec![1, 0, 2];
+        let results = run_parallel(&jobs, |x| catch(|| {
+            if *x == 0 {
+                panic!("Unexpected char {}", x);
+            }
+            10 / x
+        }));
+        assert_eq!(results, vec![Ok(10), Err("Unexpected char 0".to_string()), Ok(5)]);
+    }
+
+    #[test]
+    fn test_catch_nested() {
+        let result = catch(|| {
+            let inner = catch(|| panic!("inner"));
+            assert!(CATCHING.with(|x| x.get()));
+            inner
+        });
+        assert_eq!(result, Ok(Err("inner".to_string())));
+        assert!(!CATCHING.with(|x| x.get()));
+    }
+}
@@ -0,0 +1,301 @@
+use std::fmt::Write;
+use std::io::{self, IsTerminal};
+use std::{thread, time::{Duration, Instant}};
+
+use crate::geometry::Point;
+use crate::grid::Grid;
+
+// Drawing simulations in the terminal. Frames go to stderr, like the log,
+// so that answers on stdout stay clean.
+
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Colour {
+    Plain,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Grey,
+}
+
+impl Colour {
+    // ANSI escape switching to it
+    fn escape(&self) -> &'static str {
+        match self {
+            Colour::Plain => "\x1b[0m",
+            Colour::Red => "\x1b[31m",
+            Colour::Green => "\x1b[32m",
+            Colour::Yellow => "\x1b[33m",
+            Colour::Blue => "\x1b[34m",
+            Colour::Magenta => "\x1b[35m",
+            Colour::Cyan => "\x1b[36m",
+            Colour::Grey => "\x1b[90m",
+        }
+    }
+}
+
+// Part of the plane, both corners included
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub struct Viewport {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Viewport {
+    pub fn new(min: Point, max: Point) -> Self {
+        Viewport { min, max }
+    }
+
+    // width columns and height rows starting at corner
+    pub fn at(corner: Point, width: usize, height: usize) -> Self {
+        Viewport::new(corner, corner + Point::new(width as i64 - 1, height as i64 - 1))
+    }
+
+    // Smallest viewport around all points, None if there are none
+    pub fn around(points: impl IntoIterator<Item = Point>) -> Option<Self> {
+        points.into_iter().fold(None, |acc, x| match acc {
+            None => Some(Viewport::new(x, x)),
+            Some(v) => Some(Viewport::new(
+                Point::new(v.min.x.min(x.x), v.min.y.min(x.y)),
+                Point::new(v.max.x.max(x.x), v.max.y.max(x.y)),
+            )),
+        })
+    }
+
+    // Smallest viewport around the cells of state that are not blank
+    pub fn crop<R: Render + ?Sized>(state: &R) -> Option<Self> {
+        let bounds = state.bounds();
+        Viewport::around(bounds.points().filter(|x| !state.is_blank(state.cell(*x))))
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1).max(0) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1).max(0) as usize
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    // Row by row, left to right
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (min, max) = (self.min, self.max);
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point::new(x, y)))
+    }
+}
+
+// State that can be drawn one char per point, like a map or a simulation
+pub trait Render {
+    // Where there is something to draw, cell is only asked about these
+    fn bounds(&self) -> Viewport;
+
+    fn cell(&self, point: Point) -> char;
+
+    // Colour of a kind of cell
+    fn colour(&self, _cell: char) -> Colour {
+        Colour::Plain
+    }
+
+    // Background, cropped away around the rest
+    fn is_blank(&self, cell: char) -> bool {
+        cell == '.' || cell == ' '
+    }
+}
+
+impl Render for Grid<char> {
+    fn bounds(&self) -> Viewport {
+        Viewport::at(Point::default(), self.width(), self.height())
+    }
+
+    fn cell(&self, point: Point) -> char {
+        self[point]
+    }
+}
+
+// Rows of state seen through viewport, with ANSI colours when colours is
+// set. Points outside of the bounds of state are drawn as spaces.
+pub fn draw<R: Render + ?Sized>(state: &R, viewport: Viewport, colours: bool) -> Vec<String> {
+    let bounds = state.bounds();
+    let mut rows = vec![];
+    for y in viewport.min.y..=viewport.max.y {
+        let mut row = String::new();
+        let mut current = Colour::Plain;
+        for x in viewport.min.x..=viewport.max.x {
+            let point = Point::new(x, y);
+            let cell = if bounds.contains(point) { state.cell(point) } else { ' ' };
+            if colours && state.colour(cell) != current {
+                current = state.colour(cell);
+                row.push_str(current.escape());
+            }
+            row.push(cell);
+        }
+        if current != Colour::Plain {
+            row.push_str(Colour::Plain.escape());
+        }
+        rows.push(row);
+    }
+    rows
+}
+
+// Everything that is not blank, without colours
+pub fn draw_cropped<R: Render + ?Sized>(state: &R) -> Vec<String> {
+    Viewport::crop(state).map(|x| draw(state, x, false)).unwrap_or_default()
+}
+
+// Draws the frames of a simulation over each other, at most fps a second.
+// fps 0 turns it off, so solvers can call frame unconditionally.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Animation {
+    fps: u32,
+    viewport: Option<Viewport>,
+    colours: bool,
+    // Rows of the previous frame, the cursor goes back up over them
+    rows: usize,
+    last: Option<Instant>,
+}
+
+impl Animation {
+    pub fn new(fps: u32) -> Self {
+        let colours = io::stderr().is_terminal();
+        Animation { fps, viewport: None, colours, rows: 0, last: None }
+    }
+
+    // Always shows this part of the state instead of cropping every frame
+    pub fn viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    // On by default when stderr is a terminal
+    pub fn colours(mut self, colours: bool) -> Self {
+        self.colours = colours;
+        self
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.fps > 0
+    }
+
+    pub fn frame<R: Render + ?Sized>(&mut self, state: &R) {
+        if !self.enabled() {
+            return;
+        }
+        let text = self.frame_text(state);
+        if let Some(last) = self.last {
+            let wait = Duration::from_secs(1) / self.fps;
+            thread::sleep(wait.saturating_sub(last.elapsed()));
+        }
+        eprint!("{}", text);
+        self.last = Some(Instant::now());
+    }
+
+    // Moves the cursor back to where the previous frame started and
+    // overwrites it line by line, so the screen does not flicker
+    fn frame_text<R: Render + ?Sized>(&mut self, state: &R) -> String {
+        let rows = match self.viewport.or_else(|| Viewport::crop(state)) {
+            Some(viewport) => draw(state, viewport, self.colours),
+            None => vec![],
+        };
+        let mut text = String::new();
+        if self.rows > 0 {
+            write!(text, "\x1b[{}A\r", self.rows).unwrap();
+        }
+        for row in &rows {
+            // Clears what is left of a longer row
+            text.push_str(row);
+            text.push_str("\x1b[K\n");
+        }
+        // and of a longer frame
+        text.push_str("\x1b[J");
+        self.rows = rows.len();
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A few cells on an otherwise blank plane
+    struct Dots(Vec<(Point, char)>);
+
+    impl Render for Dots {
+        fn bounds(&self) -> Viewport {
+            Viewport::new(Point::new(-10, -10), Point::new(10, 10))
+        }
+
+        fn cell(&self, point: Point) -> char {
+            self.0.iter().find(|x| x.0 == point).map(|x| x.1).unwrap_or('.')
+        }
+
+        fn colour(&self, cell: char) -> Colour {
+            match cell {
+                '#' => Colour::Grey,
+                'o' => Colour::Yellow,
+                _ => Colour::Plain,
+            }
+        }
+    }
+
+    #[test]
+    fn test_viewport() {
+        let v = Viewport::at(Point::new(2, 1), 3, 2);
+        assert_eq!(v, Viewport::new(Point::new(2, 1), Point::new(4, 2)));
+        assert_eq!((v.width(), v.height()), (3, 2));
+        assert!(v.contains(Point::new(4, 1)) && !v.contains(Point::new(5, 1)));
+        assert_eq!(v.points().count(), 6);
+        assert_eq!(v.points().nth(3), Some(Point::new(2, 2)));
+
+        let around = Viewport::around([Point::new(3, -1), Point::new(-2, 4)]);
+        assert_eq!(around, Some(Viewport::new(Point::new(-2, -1), Point::new(3, 4))));
+        assert_eq!(Viewport::around([]), None);
+    }
+
+    #[test]
+    fn test_draw() {
+        let dots = Dots(vec![(Point::new(-1, 0), '#'), (Point::new(1, 2), 'o'), (Point::new(2, 2), 'o')]);
+        assert_eq!(Viewport::crop(&dots), Some(Viewport::new(Point::new(-1, 0), Point::new(2, 2))));
+        assert_eq!(draw_cropped(&dots), vec!["#...", "....", "..oo"]);
+        assert!(draw_cropped(&Dots(vec![])).is_empty());
+
+        // Outside of the bounds
+        let v = Viewport::new(Point::new(9, 0), Point::new(11, 0));
+        assert_eq!(draw(&dots, v, false), vec![".. "]);
+
+        let v = Viewport::new(Point::new(-1, 0), Point::new(0, 0));
+        assert_eq!(draw(&dots, v, true), vec!["\x1b[90m#\x1b[0m."]);
+        let v = Viewport::new(Point::new(0, 2), Point::new(2, 2));
+        assert_eq!(draw(&dots, v, true), vec![".\x1b[33moo\x1b[0m"]);
+    }
+
+    #[test]
+    fn test_grid() {
+        let grid = Grid::parse("#..\n.#.\n", Ok).unwrap();
+        assert_eq!(grid.bounds(), Viewport::new(Point::new(0, 0), Point::new(2, 1)));
+        assert_eq!(draw(&grid, grid.bounds(), false), vec!["#..", ".#."]);
+        assert_eq!(draw_cropped(&grid), vec!["#.", ".#"]);
+    }
+
+    #[test]
+    fn test_animation() {
+        let mut animation = Animation::new(10).colours(false);
+        let mut dots = Dots(vec![(Point::new(0, 0), '#'), (Point::new(0, 1), '#')]);
+        assert_eq!(animation.frame_text(&dots), "#\x1b[K\n#\x1b[K\n\x1b[J");
+        dots.0.pop();
+        assert_eq!(animation.frame_text(&dots), "\x1b[2A\r#\x1b[K\n\x1b[J");
+
+        let mut animation = Animation::new(10).colours(false).viewport(Viewport::at(Point::new(0, 0), 2, 1));
+        assert_eq!(animation.frame_text(&dots), "#.\x1b[K\n\x1b[J");
+        assert!(!Animation::new(0).enabled());
+    }
+}
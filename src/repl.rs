@@ -0,0 +1,182 @@
+use std::{io::{self, BufRead, Write}, str::FromStr, time::Instant};
+
+use crate::bench::fmt_duration;
+use crate::input::InputSource;
+use crate::params::Params;
+use crate::pool;
+use crate::solution::{Day, Parsed};
+
+const COMMANDS: [(&str, &str); 6] = [
+    ("part1, part2", "Run a part on the loaded input"),
+    ("params", "Show the parameters of the day"),
+    ("set <name> <value>", "Change a parameter for the next runs"),
+    ("reload", "Read and parse the input again"),
+    ("help", "Show this help"),
+    ("quit", "Leave the REPL (or Ctrl-D)"),
+];
+
+// Argument index of a day specific command, parsed as T
+pub fn parse_arg<T: FromStr>(args: &[&str], index: usize, name: &str) -> Result<T, String> {
+    let arg = args.get(index).ok_or_else(|| format!("Missing <{}>", name))?;
+    arg.parse::<T>().map_err(|_| format!("Cannot parse '{}' as <{}>", arg, name))
+}
+
+// Keeps the parsed input of a day around between commands
+pub struct Repl<'a> {
+    day: &'a Day,
+    source: InputSource,
+    input: Parsed,
+    params: Params,
+}
+
+// Reads and parses the input, errors are printable diagnostics
+fn load(day: &Day, source: &InputSource) -> Result<Parsed, String> {
+    let data = source.read(day.day)
+        .map_err(|e| format!("Cannot read {}: {}", source.name(day.day), e))?;
+    day.solver.parse_dyn(&data)
+        .map_err(|e| e.with_file(source.name(day.day)).diagnostic(&data))
+}
+
+impl<'a> Repl<'a> {
+    pub fn new(day: &'a Day, source: InputSource, params: Params) -> Result<Self, String> {
+        let input = load(day, &source)?;
+        Ok(Repl { day, source, input, params })
+    }
+
+    fn show_params(&self) -> String {
+        if self.params.is_empty() {
+            return format!("Day {} has no parameters", self.day.day);
+        }
+        self.params.iter()
+            .map(|(name, value, help)| format!("{:<20}{}", format!("{}={}", name, value), help))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn prompt(&self) -> String {
+        format!("d{}> ", self.day.day)
+    }
+
+    pub fn help(&self) -> String {
+        COMMANDS.iter()
+            .chain(self.day.solver.explore_help_dyn())
+            .map(|(usage, description)| format!("{:<20}{}", usage, description))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // Output of a single command line, None when the REPL should stop.
+    // Panics of the solver are reported instead of ending the session.
+    pub fn execute(&mut self, line: &str) -> Option<String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = words.split_first() else {
+            return Some(String::new());
+        };
+        let output = match command {
+            "quit" | "exit" => return None,
+            "help" => self.help(),
+            "part1" | "part2" => {
+                let part = if command == "part1" { 1 } else { 2 };
+                let start = Instant::now();
+                match pool::catch(|| self.day.solver.solve_dyn(&self.input, part, &self.params)) {
+                    Ok(Ok(answer)) => format!("d{}p{}: {} ({})", self.day.day, part, answer, fmt_duration(start.elapsed())),
+                    Ok(Err(e)) => format!("d{}p{}: {}", self.day.day, part, e),
+                    Err(e) => format!("d{}p{}: panicked: {}", self.day.day, part, e),
+                }
+            },
+            "params" => self.show_params(),
+            "set" => match args {
+                [name, value] => match self.params.set(name, value) {
+                    Ok(()) => format!("{}={}", name, self.params.get(name)),
+                    Err(e) => format!("error: {}", e),
+                },
+                _ => "error: Expected set <name> <value>".to_string(),
+            },
+            "reload" => match load(self.day, &self.source) {
+                Ok(input) => {
+                    self.input = input;
+                    format!("Reloaded {}", self.source.name(self.day.day))
+                },
+                // Keep working on the previous input
+                Err(e) => e.trim_end().to_string(),
+            },
+            _ => match pool::catch(|| self.day.solver.explore_dyn(&self.input, command, args)) {
+                Ok(Some(Ok(output))) => output,
+                Ok(Some(Err(e))) => format!("error: {}", e),
+                Ok(None) => format!("Unknown command '{}', try help", command),
+                Err(e) => format!("panicked: {}", e),
+            },
+        };
+        Some(output)
+    }
+}
+
+pub fn repl(day: &Day, source: InputSource, params: Params) -> Result<(), String> {
+    let mut repl = Repl::new(day, source, params)?;
+    println!("Loaded {}, type help for the commands", repl.source.name(day.day));
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("{}", repl.prompt());
+        io::stdout().flush().ok();
+        let Some(Ok(line)) = lines.next() else {
+            println!();
+            return Ok(());
+        };
+        match repl.execute(&line) {
+            Some(output) if output.is_empty() => {},
+            Some(output) => println!("{}", output),
+            None => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    fn day(day: u8) -> Day {
+        registry().into_iter().find(|x| x.day == day).unwrap()
+    }
+
+    #[test]
+    fn test_parts() {
+        let day = day(6);
+        let mut repl = Repl::new(&day, InputSource::Sample, day.solver.params_dyn()).unwrap();
+        assert!(repl.execute("part1").unwrap().starts_with("d6p1: 7 ("));
+        assert!(repl.execute("  part2 ").unwrap().starts_with("d6p2: 19 ("));
+        assert_eq!(repl.execute(""), Some(String::new()));
+        assert_eq!(repl.execute("quit"), None);
+    }
+
+    #[test]
+    fn test_commands() {
+        let day = day(6);
+        let mut repl = Repl::new(&day, InputSource::Sample, day.solver.params_dyn()).unwrap();
+        assert_eq!(repl.execute("window 14"), Some("19".to_string()));
+        assert_eq!(repl.execute("window x"), Some("error: Cannot parse 'x' as <size>".to_string()));
+        assert_eq!(repl.execute("foo"), Some("Unknown command 'foo', try help".to_string()));
+        assert!(repl.execute("help").unwrap().contains("window <size>"));
+        assert_eq!(repl.execute("reload").unwrap(), format!("Reloaded {}", InputSource::Sample.name(6)));
+    }
+
+    #[test]
+    fn test_params() {
+        let day = day(6);
+        let mut repl = Repl::new(&day, InputSource::Sample, day.solver.params_dyn()).unwrap();
+        assert!(repl.execute("params").unwrap().starts_with("part1_window=4"));
+        assert_eq!(repl.execute("set part1_window 14"), Some("part1_window=14".to_string()));
+        assert!(repl.execute("part1").unwrap().starts_with("d6p1: 19 ("));
+        assert!(repl.execute("set window 14").unwrap().starts_with("error: Unknown parameter 'window'"));
+        assert_eq!(repl.execute("set part1_window"), Some("error: Expected set <name> <value>".to_string()));
+    }
+
+    #[test]
+    fn test_missing_input() {
+        let day = day(1);
+        let err = Repl::new(&day, InputSource::Path("/nonexistent/input.txt".into()), Params::new()).err().unwrap();
+        assert!(err.starts_with("Cannot read /nonexistent/input.txt"));
+    }
+}
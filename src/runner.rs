@@ -0,0 +1,222 @@
+use std::time::{Duration, Instant};
+
+use serde_json::{Value, json};
+
+use crate::answer::Answer;
+use crate::bench::fmt_duration;
+use crate::cli::Target;
+use crate::error::AocError;
+use crate::input::InputSource;
+use crate::memory::{self, AllocStats, fmt_bytes};
+use crate::params::Params;
+use crate::pool;
+use crate::solution::Day;
+
+// Outcome of a single day/part when running everything at once
+pub struct Row {
+    pub target: Target,
+    pub input: String,
+    pub answer: Option<Answer>,
+    // Parsing plus solving
+    pub time: Duration,
+    // Only with the alloc-stats feature
+    pub memory: Option<AllocStats>,
+    pub error: Option<String>,
+}
+
+// Input and parameters of a day, or why they could not be had
+type Prepared = Result<(String, Params), String>;
+
+// Each target parses its own copy of the input so that parts of the same
+// day can run on different workers and a panic only takes down its row.
+fn run_target(day: &Day, target: Target, input: &str, prepared: &Prepared) -> Row {
+    let row = |answer, time, memory, error| Row { target, input: input.to_string(), answer, time, memory, error };
+    let (data, params) = match prepared {
+        Ok(prepared) => prepared,
+        Err(e) => return row(None, Duration::ZERO, None, Some(e.clone())),
+    };
+
+    let start = Instant::now();
+    let (result, memory) = memory::measure(|| pool::catch(|| {
+        let parsed = day.solver.parse_dyn(data)?;
+        Ok::<_, AocError>(day.solver.solve_dyn(&parsed, target.part, params))
+    }));
+    let time = start.elapsed();
+
+    match result {
+        Ok(Ok(Ok(answer))) => row(Some(answer), time, memory, None),
+        Ok(Ok(Err(e))) => row(None, time, memory, Some(e)),
+        Ok(Err(e)) => row(None, time, memory, Some(e.with_file(input).to_string())),
+        Err(msg) => row(None, time, memory, Some(format!("panicked: {}", msg))),
+    }
+}
+
+pub fn run_parallel(registry: &[Day], targets: &[Target], source: &InputSource, overrides: &[(String, String)]) -> Vec<Row> {
+    // Inputs are read up front, once per day, as stdin can only be read once
+    let days: Vec<(&Day, Prepared)> = registry.iter()
+        .filter(|day| targets.iter().any(|x| x.day == day.day))
+        .map(|day| {
+            let data = source.read(day.day)
+                .map_err(|e| format!("Cannot read {}: {}", source.name(day.day), e));
+            (day, data.and_then(|data| Ok((data, day.params(overrides)?))))
+        })
+        .collect();
+
+    let jobs: Vec<(&Day, Target, &Prepared)> = targets.iter()
+        .filter_map(|target| days.iter()
+            .find(|(day, _)| day.day == target.day)
+            .map(|(day, data)| (*day, *target, data)))
+        .collect();
+    pool::run_parallel(&jobs, |(day, target, data)| {
+        run_target(day, *target, &source.name(day.day), data)
+    })
+}
+
+fn answer_json(answer: &Answer) -> Value {
+    match answer {
+        Answer::Int(x) => json!(x),
+        Answer::Text(x) => json!(x),
+        Answer::Grid(rows) => json!(rows),
+    }
+}
+
+// One object per day and part, in the order of the rows
+pub fn to_json(rows: &[Row]) -> Value {
+    let rows: Vec<Value> = rows.iter()
+        .map(|x| json!({
+            "day": x.target.day,
+            "part": x.target.part,
+            "input": x.input,
+            "answer": x.answer.as_ref().map(answer_json),
+            "time_ns": x.time.as_nanos() as u64,
+            "memory": x.memory.map(|m| json!({ "allocs": m.count, "bytes": m.bytes, "peak": m.peak })),
+            "error": x.error,
+        }))
+        .collect();
+    Value::Array(rows)
+}
+
+// Allocs, total and peak columns, left out without the alloc-stats feature
+fn memory_columns(memory: Option<AllocStats>) -> String {
+    match (memory, memory::enabled()) {
+        (Some(m), _) => format!("  {:>9}  {:>9}  {:>9}", m.count, fmt_bytes(m.bytes), fmt_bytes(m.peak)),
+        (None, true) => format!("  {:>9}  {:>9}  {:>9}", "", "", ""),
+        (None, false) => String::new(),
+    }
+}
+
+pub fn print_table(rows: &[Row]) {
+    let header = if memory::enabled() {
+        format!("  {:>9}  {:>9}  {:>9}", "Allocs", "Total", "Peak")
+    } else {
+        String::new()
+    };
+    println!("{:>3}  {:>4}  {:<6}  {:>10}{}  Answer", "Day", "Part", "Status", "Time", header);
+    for row in rows {
+        let (status, detail) = match (&row.answer, &row.error) {
+            (_, Some(error)) => ("FAILED", error.clone()),
+            (Some(answer), None) => ("OK", answer.summary()),
+            (None, None) => ("FAILED", String::new()),
+        };
+        println!("{:>3}  {:>4}  {:<6}  {:>10}{}  {}",
+            row.target.day, row.target.part, status, fmt_duration(row.time), memory_columns(row.memory), detail);
+    }
+
+    let failed = rows.iter().filter(|x| x.error.is_some()).count();
+    println!();
+    println!("{} ok, {} failed", rows.len() - failed, failed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{Result, parse_at};
+    use crate::solution::Solution;
+
+    struct Panicky;
+
+    impl Solution for Panicky {
+        type Input = Vec<i64>;
+
+        fn parse(&self, data: &str) -> Result<Self::Input> {
+            data.lines().map(|x| parse_at(data, x)).collect()
+        }
+
+        fn part1(&self, input: &Self::Input) -> Answer {
+            Answer::from(input.iter().sum::<i64>())
+        }
+
+        fn part2(&self, input: &Self::Input) -> Answer {
+            self.part2_with(input, &self.params())
+        }
+
+        fn params(&self) -> Params {
+            Params::new().declare("index", 100, 0..=1000, "Number to pick in part 2")
+        }
+
+        fn part2_with(&self, input: &Self::Input, params: &Params) -> Answer {
+            Answer::from(input[params.int::<usize>("index")])
+        }
+    }
+
+    fn run_on(name: &str, data: &str) -> (Vec<Row>, String) {
+        run_with(name, data, &[])
+    }
+
+    fn run_with(name: &str, data: &str, overrides: &[(String, String)]) -> (Vec<Row>, String) {
+        let registry = vec![Day { day: 1, solver: &Panicky }];
+        let targets = vec![Target::new(1, 1), Target::new(1, 2)];
+        let path = std::env::temp_dir().join(format!("aoc_{}_{}.txt", name, std::process::id()));
+        std::fs::write(&path, data).unwrap();
+
+        let rows = run_parallel(&registry, &targets, &InputSource::Path(path.clone()), overrides);
+        std::fs::remove_file(&path).unwrap();
+        (rows, path.display().to_string())
+    }
+
+    #[test]
+    fn test_run_parallel() {
+        let (rows, path) = run_on("runner", "1\n2\n3\n");
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].target, Target::new(1, 1));
+        assert_eq!(rows[0].answer, Some(Answer::from(6)));
+        assert_eq!(rows[0].error, None);
+        assert_eq!(rows[0].memory.is_some(), memory::enabled());
+        assert_eq!(rows[1].answer, None);
+        assert!(rows[1].error.as_ref().unwrap().contains("index out of bounds"));
+        assert_eq!(rows[1].input, path);
+
+        let value = to_json(&rows);
+        assert_eq!(value[0]["answer"], json!(6));
+        assert_eq!(value[0]["error"], Value::Null);
+        assert_eq!(value[1]["part"], json!(2));
+        assert_eq!(value[1]["answer"], Value::Null);
+        assert!(value[1]["error"].as_str().unwrap().contains("index out of bounds"));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let (rows, path) = run_on("runner_bad", "1\nx\n3\n");
+        for row in rows {
+            assert_eq!(row.answer, None);
+            assert_eq!(row.error, Some(format!("{}:2:1: Cannot parse 'x' as i64", path)));
+        }
+    }
+
+    #[test]
+    fn test_params() {
+        let (rows, _) = run_with("runner_params", "1\n2\n3\n", &[("index".to_string(), "1".to_string())]);
+        assert_eq!(rows[1].answer, Some(Answer::from(2)));
+
+        let (rows, _) = run_with("runner_params_bad", "1\n", &[("size".to_string(), "1".to_string())]);
+        assert_eq!(rows[0].error, Some("Day 1: Unknown parameter 'size', expected one of index".to_string()));
+    }
+
+    #[test]
+    fn test_answer_json() {
+        assert_eq!(answer_json(&Answer::from("CMZ")), json!("CMZ"));
+        let grid = Answer::Grid(vec!["#.".to_string(), ".#".to_string()]);
+        assert_eq!(answer_json(&grid), json!(["#.", ".#"]));
+    }
+}
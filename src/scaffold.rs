@@ -0,0 +1,175 @@
+use std::{fs, path::{Path, PathBuf}};
+
+// Starting point of a new day. Parses one number per line so that it
+// builds, passes clippy and runs right away.
+const TEMPLATE: &str = "\
+use crate::answer::Answer;
+use crate::error::{Result, parse_at};
+use crate::solution::Solution;
+
+pub struct DayNN;
+
+pub fn parse_input(data: &str) -> Result<Vec<i64>> {
+    data.lines()
+        .filter(|x| !x.trim().is_empty())
+        .map(|x| parse_at(data, x.trim()))
+        .collect()
+}
+
+impl Solution for DayNN {
+    type Input = Vec<i64>;
+
+    fn parse(&self, data: &str) -> Result<Self::Input> {
+        parse_input(data)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Answer {
+        Answer::from(input.len())
+    }
+
+    fn part2(&self, _input: &Self::Input) -> Answer {
+        Answer::from(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_input() {
+        assert_eq!(parse_input(\"1\\n2\\n\").unwrap(), vec![1, 2]);
+    }
+}
+";
+
+const SAMPLE_ANSWERS: &str = "\
+# Expected answers for sample_input.txt
+
+# part1 =
+# part2 =
+";
+
+// Name and contents of every file of src/dayNN/
+pub fn module_files(day: u8) -> Vec<(String, String)> {
+    let module = format!("day{:02}", day);
+    let solver = format!("Day{:02}", day);
+    vec![
+        ("mod.rs".to_string(), format!("pub mod {};\n\npub use self::{}::{{{}, parse_input}};\n", module, module, solver)),
+        (format!("{}.rs", module), TEMPLATE.replace("DayNN", &solver)),
+        ("input.txt".to_string(), String::new()),
+        ("sample_input.txt".to_string(), String::new()),
+        ("sample_answers.toml".to_string(), SAMPLE_ANSWERS.to_string()),
+    ]
+}
+
+// Adds the day to the days! block of lib.rs, keeping it sorted
+pub fn register(lib: &str, day: u8) -> Result<String, String> {
+    let start = lib.find("days! {\n").ok_or("Cannot find the days! block")? + "days! {\n".len();
+    let end = start + lib[start..].find("}\n").ok_or("Cannot find the end of the days! block")?;
+
+    let mut lines: Vec<String> = lib[start..end].lines().map(|x| x.to_string()).collect();
+    let number = |line: &str| line.trim().split(" =>").next().and_then(|x| x.parse::<u8>().ok());
+    if lines.iter().any(|x| number(x) == Some(day)) {
+        return Err(format!("Day {} is registered already", day));
+    }
+    let index = lines.iter().position(|x| number(x).is_some_and(|x| x > day)).unwrap_or(lines.len());
+    lines.insert(index, format!("    {} => day{:02}::Day{:02},", day, day, day));
+
+    Ok(format!("{}{}\n{}", &lib[..start], lines.join("\n"), &lib[end..]))
+}
+
+// Creates src/dayNN/ under root and registers it in src/lib.rs. An input
+// fetched before is kept, anything else existing is an error.
+pub fn new_day_in(root: &Path, day: u8) -> Result<Vec<PathBuf>, String> {
+    let dir = root.join("src").join(format!("day{:02}", day));
+    let lib_path = root.join("src").join("lib.rs");
+    let lib = fs::read_to_string(&lib_path)
+        .map_err(|e| format!("Cannot read {}: {}", lib_path.display(), e))?;
+    let lib = register(&lib, day)?;
+
+    let files = module_files(day);
+    for (name, _) in &files {
+        let path = dir.join(name);
+        if path.exists() && name.ends_with(".rs") {
+            return Err(format!("{} exists already", path.display()));
+        }
+    }
+
+    fs::create_dir_all(&dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+    let mut created = vec![];
+    for (name, contents) in files {
+        let path = dir.join(name);
+        if path.exists() {
+            continue;
+        }
+        fs::write(&path, contents).map_err(|e| format!("Cannot write {}: {}", path.display(), e))?;
+        created.push(path);
+    }
+    fs::write(&lib_path, lib).map_err(|e| format!("Cannot write {}: {}", lib_path.display(), e))?;
+    Ok(created)
+}
+
+pub fn new_day(day: u8) -> Result<Vec<PathBuf>, String> {
+    new_day_in(Path::new(env!("CARGO_MANIFEST_DIR")), day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    const LIB: &str = "\
+pub mod answer;
+
+days! {
+    1 => day01::Day01,
+    7 => day07::Day07,
+}
+";
+
+    #[test]
+    fn test_register() {
+        let lib = register(LIB, 5).unwrap();
+        assert!(lib.ends_with("days! {\n    1 => day01::Day01,\n    5 => day05::Day05,\n    7 => day07::Day07,\n}\n"));
+        let lib = register(&lib, 15).unwrap();
+        assert!(lib.ends_with("    7 => day07::Day07,\n    15 => day15::Day15,\n}\n"));
+        assert!(lib.starts_with("pub mod answer;\n"));
+
+        assert_eq!(register(LIB, 7), Err("Day 7 is registered already".to_string()));
+        assert!(register("pub mod answer;\n", 7).is_err());
+    }
+
+    #[test]
+    fn test_module_files() {
+        let files = module_files(15);
+        let names: Vec<&str> = files.iter().map(|(x, _)| x.as_str()).collect();
+        assert_eq!(names, vec!["mod.rs", "day15.rs", "input.txt", "sample_input.txt", "sample_answers.toml"]);
+        assert_eq!(files[0].1, "pub mod day15;\n\npub use self::day15::{Day15, parse_input};\n");
+        assert!(files[1].1.contains("pub struct Day15;\n"));
+        assert!(files[1].1.contains("impl Solution for Day15 {"));
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = env::temp_dir().join(format!("aoc_scaffold_{}", process::id()));
+        fs::create_dir_all(root.join("src").join("day15")).unwrap();
+        fs::write(root.join("src").join("lib.rs"), LIB).unwrap();
+        // Fetched before
+        fs::write(root.join("src").join("day15").join("input.txt"), "42\n").unwrap();
+
+        let created = new_day_in(&root, 15).unwrap();
+        let dir = root.join("src").join("day15");
+        let input = fs::read_to_string(dir.join("input.txt")).unwrap();
+        let lib = fs::read_to_string(root.join("src").join("lib.rs")).unwrap();
+        let again = new_day_in(&root, 15);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(created.len(), 4);
+        assert!(!created.contains(&dir.join("input.txt")));
+        assert_eq!(input, "42\n");
+        assert!(lib.contains("    15 => day15::Day15,\n"));
+        assert_eq!(again, Err("Day 15 is registered already".to_string()));
+    }
+}
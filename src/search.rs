@@ -0,0 +1,260 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+// Searches over states handed out by a neighbour closure, so nothing has
+// to build a graph first. A state can be anything hashable: a Point, a
+// (Point, Direction), a whole board...
+
+// What a search found out: how far each reached state is from the start
+// and which state it was reached from
+#[derive(Debug)]
+pub struct Search<N> {
+    distances: HashMap<N, u64>,
+    predecessors: HashMap<N, N>,
+}
+
+impl<N: Clone + Eq + Hash> Search<N> {
+    fn new() -> Self {
+        Search { distances: HashMap::new(), predecessors: HashMap::new() }
+    }
+
+    // None if node was never reached
+    pub fn distance(&self, node: &N) -> Option<u64> {
+        self.distances.get(node).copied()
+    }
+
+    pub fn distances(&self) -> &HashMap<N, u64> {
+        &self.distances
+    }
+
+    // Starts have no predecessor
+    pub fn predecessors(&self) -> &HashMap<N, N> {
+        &self.predecessors
+    }
+
+    // States from a start to node, both included
+    pub fn path(&self, node: &N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(node) {
+            return None;
+        }
+        let mut path = vec![node.clone()];
+        while let Some(prev) = self.predecessors.get(path.last().unwrap()) {
+            path.push(prev.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+// Every step costs 1
+pub fn bfs<N, F, I>(start: N, neighbours: F) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    bfs_multi([start], neighbours)
+}
+
+// BFS from all starts at once, distances are to the closest one
+pub fn bfs_multi<N, S, F, I>(starts: S, mut neighbours: F) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    S: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if !search.distances.contains_key(&start) {
+            search.distances.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        let distance = search.distances[&node];
+        for next in neighbours(&node) {
+            if search.distances.contains_key(&next) {
+                continue;
+            }
+            search.distances.insert(next.clone(), distance + 1);
+            search.predecessors.insert(next.clone(), node.clone());
+            queue.push_back(next);
+        }
+    }
+    search
+}
+
+// neighbours gives each next state with the cost of getting there
+pub fn dijkstra<N, F, I>(start: N, neighbours: F) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    best_first(start, neighbours, |_| 0, |_| false).0
+}
+
+// Cheapest path to the first state goal accepts, with its cost. heuristic
+// must never overestimate the remaining cost, |_| 0 is always safe.
+pub fn astar<N, F, I, H, G>(start: N, neighbours: F, heuristic: H, goal: G) -> Option<(u64, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+    H: FnMut(&N) -> u64,
+    G: FnMut(&N) -> bool,
+{
+    let (search, found) = best_first(start, neighbours, heuristic, goal);
+    let found = found?;
+    Some((search.distances[&found], search.path(&found)?))
+}
+
+// Entry of the open set, the heap pops the lowest estimate first
+struct Queued<N> {
+    cost: u64,
+    estimate: u64,
+    node: N,
+}
+
+impl<N> PartialEq for Queued<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl<N> Eq for Queued<N> {}
+
+impl<N> PartialOrd for Queued<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> Ord for Queued<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.cmp(&self.estimate)
+    }
+}
+
+// Shared by dijkstra and astar. Stops at the first state goal accepts and
+// returns it along with what was explored so far.
+fn best_first<N, F, I, H, G>(start: N, mut neighbours: F, mut heuristic: H, mut goal: G) -> (Search<N>, Option<N>)
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+    H: FnMut(&N) -> u64,
+    G: FnMut(&N) -> bool,
+{
+    let mut search = Search::new();
+    let mut open = BinaryHeap::new();
+    search.distances.insert(start.clone(), 0);
+    open.push(Queued { cost: 0, estimate: heuristic(&start), node: start });
+
+    while let Some(Queued { cost, node, .. }) = open.pop() {
+        if cost > search.distances[&node] {
+            // A cheaper way there was queued after this one
+            continue;
+        }
+        if goal(&node) {
+            return (search, Some(node));
+        }
+        for (next, step) in neighbours(&node) {
+            let next_cost = cost + step;
+            if search.distances.get(&next).is_some_and(|x| *x <= next_cost) {
+                continue;
+            }
+            search.distances.insert(next.clone(), next_cost);
+            search.predecessors.insert(next.clone(), node.clone());
+            let estimate = next_cost + heuristic(&next);
+            open.push(Queued { cost: next_cost, estimate, node: next });
+        }
+    }
+    (search, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point;
+    use crate::grid::Grid;
+
+    const MAZE: &str = "\
+S..#....
+.#.#.##.
+.#...#..
+.####.#.
+......#E
+";
+
+    fn maze() -> (Grid<char>, Point, Point) {
+        let grid = Grid::parse(MAZE, Ok).unwrap();
+        let start = Point::from_cell(grid.position(|x| *x == 'S').unwrap());
+        let end = Point::from_cell(grid.position(|x| *x == 'E').unwrap());
+        (grid, start, end)
+    }
+
+    fn open(grid: &Grid<char>, point: Point) -> Vec<Point> {
+        point.neighbours4().filter(|x| grid.at(*x).is_some_and(|c| *c != '#')).collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let (grid, start, end) = maze();
+        let search = bfs(start, |x| open(&grid, *x));
+        assert_eq!(search.distance(&start), Some(0));
+        assert_eq!(search.distance(&end), Some(15));
+        assert_eq!(search.distance(&Point::new(3, 0)), None);
+
+        let path = search.path(&end).unwrap();
+        assert_eq!(path.len(), 16);
+        assert_eq!(path[0], start);
+        assert_eq!(path[15], end);
+        assert!(path.windows(2).all(|x| x[0].manhattan(x[1]) == 1));
+        assert_eq!(search.predecessors().get(&start), None);
+        assert_eq!(search.path(&Point::new(3, 0)), None);
+    }
+
+    #[test]
+    fn test_bfs_multi() {
+        let (grid, start, end) = maze();
+        let starts = [start, Point::new(4, 0)];
+        let search = bfs_multi(starts, |x| open(&grid, *x));
+        assert_eq!(search.distance(&Point::new(4, 0)), Some(0));
+        assert_eq!(search.distance(&end), Some(7));
+        assert_eq!(search.path(&end).unwrap()[0], Point::new(4, 0));
+        assert_eq!(search.distances().len(), grid.iter().filter(|(_, x)| **x != '#').count());
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // Going through 1 is cheaper than the direct edge
+        let edges = HashMap::from([
+            (0, vec![(1, 2), (2, 10)]),
+            (1, vec![(2, 3), (3, 20)]),
+            (2, vec![(3, 1)]),
+        ]);
+        let search = dijkstra(0, |x| edges.get(x).cloned().unwrap_or_default());
+        assert_eq!(search.distance(&2), Some(5));
+        assert_eq!(search.distance(&3), Some(6));
+        assert_eq!(search.path(&3), Some(vec![0, 1, 2, 3]));
+        assert_eq!(search.distance(&4), None);
+    }
+
+    #[test]
+    fn test_astar() {
+        let (grid, start, end) = maze();
+        let neighbours = |x: &Point| open(&grid, *x).into_iter().map(|x| (x, 1));
+        let heuristic = |x: &Point| x.manhattan(end) as u64;
+
+        let (cost, path) = astar(start, neighbours, heuristic, |x| *x == end).unwrap();
+        assert_eq!(cost, 15);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+        assert_eq!(astar(start, neighbours, |_| 0, |x| *x == end).unwrap().0, 15);
+        assert_eq!(astar(start, neighbours, heuristic, |x| *x == Point::new(3, 0)), None);
+    }
+}
@@ -0,0 +1,166 @@
+use std::any::Any;
+
+use crate::answer::Answer;
+use crate::error::Result;
+use crate::params::Params;
+
+// Output of a day specific REPL command, or why it failed
+pub type Explored = std::result::Result<String, String>;
+
+// Answer of a part, or why the parameters do not work with the input
+pub type Solved = std::result::Result<Answer, String>;
+
+// Every day implements this. The input is parsed once and then shared
+// by both parts, so parts which need to mutate it must clone it first.
+// Parsing reports bad input as an error, parts can assume it is valid.
+// Solvers are shared between the worker threads of --all, hence Sync.
+pub trait Solution: Sync {
+    type Input: 'static;
+
+    fn parse(&self, data: &str) -> Result<Self::Input>;
+    fn part1(&self, input: &Self::Input) -> Answer;
+    fn part2(&self, input: &Self::Input) -> Answer;
+
+    // Tunable numbers of the parts with their defaults, see params.rs.
+    // Days declaring any solve in part1_with/part2_with and let part1/part2
+    // call those with self.params().
+    fn params(&self) -> Params {
+        Params::new()
+    }
+
+    fn part1_with(&self, input: &Self::Input, _params: &Params) -> Answer {
+        self.part1(input)
+    }
+
+    fn part2_with(&self, input: &Self::Input, _params: &Params) -> Answer {
+        self.part2(input)
+    }
+
+    // Runs before part1_with/part2_with. The declared ranges are checked
+    // when a parameter is set, this is for limits which depend on the
+    // input, like a window longer than the datastream, and for input only
+    // one of the parts cannot handle.
+    fn check_params(&self, _input: &Self::Input, _part: u8, _params: &Params) -> std::result::Result<(), String> {
+        Ok(())
+    }
+
+    // Day specific commands of the REPL, like trying another window size
+    // or looking at intermediate state. None means unknown command.
+    fn explore(&self, _input: &Self::Input, _command: &str, _args: &[&str]) -> Option<Explored> {
+        None
+    }
+
+    // (usage, description) of every command handled by explore
+    fn explore_help(&self) -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+}
+
+pub type Parsed = Box<dyn Any>;
+
+// Object safe version of Solution so that different days with different
+// Input types can live together in the registry
+pub trait DynSolution: Sync {
+    fn parse_dyn(&self, data: &str) -> Result<Parsed>;
+    fn params_dyn(&self) -> Params;
+    fn check_dyn(&self, input: &Parsed, part: u8, params: &Params) -> std::result::Result<(), String>;
+    fn solve_dyn(&self, input: &Parsed, part: u8, params: &Params) -> Solved;
+    fn explore_dyn(&self, input: &Parsed, command: &str, args: &[&str]) -> Option<Explored>;
+    fn explore_help_dyn(&self) -> &'static [(&'static str, &'static str)];
+}
+
+impl<S: Solution> DynSolution for S {
+    fn parse_dyn(&self, data: &str) -> Result<Parsed> {
+        Ok(Box::new(self.parse(data)?))
+    }
+
+    fn params_dyn(&self) -> Params {
+        self.params()
+    }
+
+    fn check_dyn(&self, input: &Parsed, part: u8, params: &Params) -> std::result::Result<(), String> {
+        let input = input.downcast_ref::<S::Input>()
+            .expect("Parsed input does not belong to this solution");
+        self.check_params(input, part, params)
+    }
+
+    fn solve_dyn(&self, input: &Parsed, part: u8, params: &Params) -> Solved {
+        self.check_dyn(input, part, params)?;
+        let input = input.downcast_ref::<S::Input>()
+            .expect("Parsed input does not belong to this solution");
+        match part {
+            1 => Ok(self.part1_with(input, params)),
+            2 => Ok(self.part2_with(input, params)),
+            _ => panic!("Invalid part: {}", part),
+        }
+    }
+
+    fn explore_dyn(&self, input: &Parsed, command: &str, args: &[&str]) -> Option<Explored> {
+        let input = input.downcast_ref::<S::Input>()
+            .expect("Parsed input does not belong to this solution");
+        self.explore(input, command, args)
+    }
+
+    fn explore_help_dyn(&self) -> &'static [(&'static str, &'static str)] {
+        self.explore_help()
+    }
+}
+
+pub struct Day {
+    pub day: u8,
+    pub solver: &'static dyn DynSolution,
+}
+
+impl Day {
+    // Declared parameters of the day with the --param overrides applied
+    pub fn params(&self, overrides: &[(String, String)]) -> std::result::Result<Params, String> {
+        let mut params = self.solver.params_dyn();
+        for (name, value) in overrides {
+            params.set(name, value).map_err(|e| format!("Day {}: {}", self.day, e))?;
+        }
+        Ok(params)
+    }
+}
+
+// Declares the day modules, builds the registry from them and generates
+// the sample tests, so that adding a new day only needs a single line here:
+//
+//     days! {
+//         1 => day01::Day01,
+//     }
+macro_rules! days {
+    ($($num:literal => $module:ident :: $solver:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        pub fn registry() -> Vec<$crate::solution::Day> {
+            vec![
+                $($crate::solution::Day { day: $num, solver: &$module::$module::$solver },)*
+            ]
+        }
+
+        // One test per day and part, checking sample_input.txt against
+        // src/dayNN/sample_answers.toml
+        #[cfg(test)]
+        mod sample_tests {
+            $(
+                mod $module {
+                    fn check(part: u8) {
+                        if let Err(msg) = $crate::verify::check_sample(&$crate::registry(), $num, part) {
+                            panic!("{}", msg);
+                        }
+                    }
+
+                    #[test]
+                    fn part1() {
+                        check(1);
+                    }
+
+                    #[test]
+                    fn part2() {
+                        check(2);
+                    }
+                }
+            )*
+        }
+    };
+}
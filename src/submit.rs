@@ -0,0 +1,287 @@
+use std::{fs, path::{Path, PathBuf}, time::{SystemTime, UNIX_EPOCH}};
+
+use serde_json::{Value, json};
+
+use crate::client::Client;
+
+// Personal, so not committed, just like the bench baseline
+pub fn history_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("submissions.json")
+}
+
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    // Wrong without a hint, like for text answers
+    Wrong,
+    // Rate limited, the answer was not checked
+    TooSoon,
+    // The part is solved already or its first part is not
+    WrongLevel,
+    Unknown,
+}
+
+const VERDICTS: [(Verdict, &str); 7] = [
+    (Verdict::Correct, "correct"),
+    (Verdict::TooHigh, "too high"),
+    (Verdict::TooLow, "too low"),
+    (Verdict::Wrong, "wrong"),
+    (Verdict::TooSoon, "too soon"),
+    (Verdict::WrongLevel, "wrong level"),
+    (Verdict::Unknown, "unknown"),
+];
+
+impl Verdict {
+    pub fn name(&self) -> &'static str {
+        VERDICTS.iter().find(|(x, _)| x == self).unwrap().1
+    }
+
+    pub fn from_name(name: &str) -> Option<Verdict> {
+        VERDICTS.iter().find(|(_, x)| *x == name).map(|(x, _)| *x)
+    }
+
+    // Reads the answer page the site sends back
+    pub fn from_response(body: &str) -> Verdict {
+        if body.contains("That's the right answer") {
+            Verdict::Correct
+        } else if body.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if body.contains("your answer is too low") {
+            Verdict::TooLow
+        } else if body.contains("That's not the right answer") {
+            Verdict::Wrong
+        } else if body.contains("You gave an answer too recently") {
+            Verdict::TooSoon
+        } else if body.contains("You don't seem to be solving the right level") {
+            Verdict::WrongLevel
+        } else {
+            Verdict::Unknown
+        }
+    }
+
+    fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+    }
+}
+
+// Text of the <article> holding the message of the answer page
+pub fn response_message(body: &str) -> String {
+    let article = match (body.find("<article>"), body.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &body[start + "<article>".len()..end],
+        _ => body,
+    };
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {},
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Attempt {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+    pub message: String,
+    // Seconds since the epoch
+    pub time: u64,
+}
+
+// Every answer sent so far and what the site said about it
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+#[derive(Default)]
+pub struct History {
+    pub attempts: Vec<Attempt>,
+}
+
+impl History {
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let attempts = value["attempts"].as_array().ok_or("Missing attempts array")?;
+        let mut history = History::default();
+        for attempt in attempts {
+            let int = |key: &str| attempt[key].as_u64().ok_or(format!("Missing {} in {}", key, attempt));
+            let text = |key: &str| attempt[key].as_str().map(|x| x.to_string()).ok_or(format!("Missing {} in {}", key, attempt));
+            let verdict = text("verdict")?;
+            history.attempts.push(Attempt {
+                day: int("day")? as u8,
+                part: int("part")? as u8,
+                answer: text("answer")?,
+                verdict: Verdict::from_name(&verdict).ok_or(format!("Unknown verdict '{}'", verdict))?,
+                message: text("message")?,
+                time: int("time")?,
+            });
+        }
+        Ok(history)
+    }
+
+    pub fn to_json(&self) -> Value {
+        let attempts: Vec<Value> = self.attempts.iter()
+            .map(|x| json!({
+                "day": x.day,
+                "part": x.part,
+                "answer": x.answer,
+                "verdict": x.verdict.name(),
+                "message": x.message,
+                "time": x.time,
+            }))
+            .collect();
+        json!({ "attempts": attempts })
+    }
+
+    // A missing file is an empty history
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(History::default());
+        }
+        let data = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        let value: Value = serde_json::from_str(&data)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        History::from_json(&value).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let data = serde_json::to_string_pretty(&self.to_json()).unwrap();
+        fs::write(path, data + "\n").map_err(|e| format!("Cannot write {}: {}", path.display(), e))
+    }
+
+    // Why answer is known to be wrong without asking the site again. Too
+    // high and too low responses narrow down where the answer can be.
+    pub fn check(&self, day: u8, part: u8, answer: &str) -> Result<(), String> {
+        let attempts: Vec<&Attempt> = self.attempts.iter().filter(|x| x.day == day && x.part == part).collect();
+        if let Some(correct) = attempts.iter().find(|x| x.verdict == Verdict::Correct) {
+            return Err(format!("Day {} part {} is solved already, the answer was {}", day, part, correct.answer));
+        }
+        if let Some(wrong) = attempts.iter().find(|x| x.answer == answer && x.verdict.is_wrong()) {
+            return Err(format!("{} was submitted before and is {}", answer, wrong.verdict.name()));
+        }
+
+        let Ok(value) = answer.parse::<i64>() else {
+            return Ok(());
+        };
+        let bound = |verdict: Verdict| attempts.iter()
+            .filter(move |x| x.verdict == verdict)
+            .filter_map(|x| x.answer.parse::<i64>().ok());
+        if let Some(high) = bound(Verdict::TooHigh).min() {
+            if value >= high {
+                return Err(format!("{} cannot be right, {} was too high already", answer, high));
+            }
+        }
+        if let Some(low) = bound(Verdict::TooLow).max() {
+            if value <= low {
+                return Err(format!("{} cannot be right, {} was too low already", answer, low));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Posts answer unless the history knows it is wrong, and records the
+// response in the history file
+pub fn submit(client: &Client, path: &Path, day: u8, part: u8, answer: &str) -> Result<Attempt, String> {
+    let mut history = History::load(path)?;
+    history.check(day, part, answer)?;
+
+    let part_text = part.to_string();
+    let body = client.post(&format!("/day/{}/answer", day), &[("level", &part_text), ("answer", answer)])
+        .map_err(|e| format!("Cannot submit day {} part {}: {}", day, part, e))?;
+    let attempt = Attempt {
+        day,
+        part,
+        answer: answer.to_string(),
+        verdict: Verdict::from_response(&body),
+        message: response_message(&body),
+        time: SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_secs()).unwrap_or(0),
+    };
+    history.attempts.push(attempt.clone());
+    history.save(path)?;
+    Ok(attempt)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+    use crate::config::Config;
+    use crate::mock_server::serve;
+
+    fn attempt(answer: &str, verdict: Verdict) -> Attempt {
+        Attempt { day: 1, part: 1, answer: answer.to_string(), verdict, message: String::new(), time: 0 }
+    }
+
+    fn page(message: &str) -> String {
+        format!("<html><body><main><article><p>{}</p></article></main></body></html>", message)
+    }
+
+    #[test]
+    fn test_verdict() {
+        let body = page("That's not the right answer; your answer is too high.  If you're stuck, <a href=\"/2022/day/1\">[Return to Day 1]</a>");
+        assert_eq!(Verdict::from_response(&body), Verdict::TooHigh);
+        assert_eq!(response_message(&body), "That's not the right answer; your answer is too high. If you're stuck, [Return to Day 1]");
+        assert_eq!(Verdict::from_response(&page("That's the right answer! You are one gold star closer.")), Verdict::Correct);
+        assert_eq!(Verdict::from_response(&page("You gave an answer too recently")), Verdict::TooSoon);
+        assert_eq!(Verdict::from_response("<html></html>"), Verdict::Unknown);
+        for (verdict, name) in VERDICTS {
+            assert_eq!(Verdict::from_name(name), Some(verdict));
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        let mut history = History::default();
+        history.attempts.push(attempt("100", Verdict::TooHigh));
+        history.attempts.push(attempt("120", Verdict::TooHigh));
+        history.attempts.push(attempt("50", Verdict::TooLow));
+        history.attempts.push(attempt("ABC", Verdict::Wrong));
+
+        assert!(history.check(1, 1, "75").is_ok());
+        assert!(history.check(1, 1, "ABD").is_ok());
+        assert_eq!(history.check(1, 1, "ABC"), Err("ABC was submitted before and is wrong".to_string()));
+        assert_eq!(history.check(1, 1, "100"), Err("100 was submitted before and is too high".to_string()));
+        assert_eq!(history.check(1, 1, "110"), Err("110 cannot be right, 100 was too high already".to_string()));
+        assert_eq!(history.check(1, 1, "50"), Err("50 was submitted before and is too low".to_string()));
+        assert_eq!(history.check(1, 1, "20"), Err("20 cannot be right, 50 was too low already".to_string()));
+        // Bounds are per day and part
+        assert!(history.check(1, 2, "200").is_ok());
+
+        history.attempts.push(attempt("75", Verdict::Correct));
+        assert!(history.check(1, 1, "76").unwrap_err().contains("solved already"));
+    }
+
+    #[test]
+    fn test_submit() {
+        let path = env::temp_dir().join(format!("aoc_submissions_{}.json", process::id()));
+        let too_high = page("That's not the right answer; your answer is too high.");
+        let server = serve(vec![(200, &too_high), (200, &page("That's the right answer!"))]);
+        let client = Client::new(Config { session: Some("abc".to_string()), base_url: server.url.clone() });
+
+        assert_eq!(submit(&client, &path, 3, 2, "500").unwrap().verdict, Verdict::TooHigh);
+        // Refused without asking the server
+        assert!(submit(&client, &path, 3, 2, "600").is_err());
+        assert_eq!(submit(&client, &path, 3, 2, "400").unwrap().verdict, Verdict::Correct);
+
+        let requests = server.requests();
+        let history = History::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(requests[0].starts_with("POST /2022/day/3/answer HTTP/1.1\r\n"));
+        assert!(requests[0].ends_with("level=2&answer=500"));
+        let answers: Vec<(&str, Verdict)> = history.attempts.iter().map(|x| (x.answer.as_str(), x.verdict)).collect();
+        assert_eq!(answers, vec![("500", Verdict::TooHigh), ("400", Verdict::Correct)]);
+        assert_eq!(history.attempts[0].message, "That's not the right answer; your answer is too high.");
+    }
+}
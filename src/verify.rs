@@ -0,0 +1,329 @@
+use std::{collections::HashMap, fmt, fs, path::{Path, PathBuf}};
+
+use crate::answer::{Answer, canonical_grid};
+use crate::input::{InputSource, day_dir};
+use crate::pool;
+use crate::solution::Day;
+
+pub fn answers_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("answers.toml")
+}
+
+// Flat partN = ... table for sample_input.txt of a single day
+pub fn sample_answers_path(day: u8) -> PathBuf {
+    day_dir(day).join("sample_answers.toml")
+}
+
+// Known answers keyed by (day, input name, part). The input name is the
+// file name under src/dayNN/ without the .txt extension.
+#[derive(Debug)]
+pub struct Answers {
+    entries: HashMap<(u8, String, u8), String>,
+}
+
+fn parse_key(key: &str, prefix: &str) -> Result<u8, String> {
+    key.strip_prefix(prefix)
+        .and_then(|x| x.parse::<u8>().ok())
+        .ok_or_else(|| format!("Invalid key '{}', expected {}N", key, prefix))
+}
+
+impl Answers {
+    fn insert_parts(&mut self, day: u8, input: &str, parts: &toml::Table) -> Result<(), String> {
+        for (part_key, value) in parts {
+            let part = parse_key(part_key, "part")?;
+            let expected = match value {
+                toml::Value::Integer(x) => x.to_string(),
+                toml::Value::String(x) => canonical_grid(x),
+                _ => return Err(format!("day{:02}.{}.{} should be an integer or a string", day, input, part_key)),
+            };
+            self.entries.insert((day, input.to_string(), part), expected);
+        }
+        Ok(())
+    }
+
+    pub fn parse(data: &str) -> Result<Self, String> {
+        let table = data.parse::<toml::Table>().map_err(|e| e.to_string())?;
+        let mut answers = Answers { entries: HashMap::new() };
+
+        for (day_key, inputs) in &table {
+            let day = parse_key(day_key, "day")?;
+            let inputs = inputs.as_table()
+                .ok_or_else(|| format!("[{}] should contain one table per input", day_key))?;
+            for (input, parts) in inputs {
+                let parts = parts.as_table()
+                    .ok_or_else(|| format!("[{}.{}] should be a table", day_key, input))?;
+                answers.insert_parts(day, input, parts)?;
+            }
+        }
+        Ok(answers)
+    }
+
+    // Adds the contents of a sample_answers.toml of day
+    pub fn parse_sample(&mut self, day: u8, data: &str) -> Result<(), String> {
+        let table = data.parse::<toml::Table>().map_err(|e| e.to_string())?;
+        self.insert_parts(day, "sample_input", &table)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        Answers::parse(&data).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    // answers.toml plus the sample answers of the given days
+    pub fn load_all(days: &[u8]) -> Result<Self, String> {
+        let mut answers = Answers::load(&answers_path())?;
+        for day in days {
+            let path = sample_answers_path(*day);
+            if !path.exists() {
+                continue;
+            }
+            let data = fs::read_to_string(&path)
+                .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+            answers.parse_sample(*day, &data).map_err(|e| format!("{}: {}", path.display(), e))?;
+        }
+        Ok(answers)
+    }
+
+    pub fn get(&self, day: u8, input: &str, part: u8) -> Option<&String> {
+        self.entries.get(&(day, input.to_string(), part))
+    }
+
+    // Names of all inputs of a day with stored answers, "input" always included
+    pub fn inputs(&self, day: u8) -> Vec<String> {
+        let mut inputs: Vec<String> = self.entries.keys()
+            .filter(|(x, _, _)| *x == day)
+            .map(|(_, input, _)| input.clone())
+            .collect();
+        inputs.push("input".to_string());
+        inputs.sort();
+        inputs.dedup();
+        inputs
+    }
+}
+
+#[derive(PartialEq,Eq)]
+#[derive(Debug)]
+pub enum Status {
+    Pass,
+    Fail(String),
+    Missing,
+    Error(String),
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "PASS"),
+            Status::Fail(_) => write!(f, "FAIL"),
+            Status::Missing => write!(f, "MISSING"),
+            Status::Error(_) => write!(f, "ERROR"),
+        }
+    }
+}
+
+pub fn check(expected: Option<&String>, answer: &Answer) -> Status {
+    match expected {
+        None => Status::Missing,
+        Some(expected) if *expected == answer.canonical() => Status::Pass,
+        Some(expected) => Status::Fail(expected.clone()),
+    }
+}
+
+pub struct Row {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub answer: Option<Answer>,
+    pub status: Status,
+}
+
+fn verify_input(day: &Day, input: &str, answers: &Answers) -> Vec<Row> {
+    let source = InputSource::Path(day_dir(day.day).join(format!("{}.txt", input)));
+    let row = |part, answer, status| Row { day: day.day, part, input: input.to_string(), answer, status };
+
+    let data = match source.read(day.day) {
+        Ok(data) => data,
+        Err(e) => {
+            let msg = format!("Cannot read {}: {}", source.name(day.day), e);
+            return vec![row(1, None, Status::Error(msg.clone())), row(2, None, Status::Error(msg))];
+        }
+    };
+
+    let parsed = match day.solver.parse_dyn(&data) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            let msg = e.with_file(source.name(day.day)).to_string();
+            return vec![row(1, None, Status::Error(msg.clone())), row(2, None, Status::Error(msg))];
+        }
+    };
+    // Stored answers are for the puzzle as asked, so with the defaults
+    let params = day.solver.params_dyn();
+    let mut rows = vec![];
+    for part in [1, 2] {
+        // A panicking part only fails its own row, like in --all
+        match pool::catch(|| day.solver.solve_dyn(&parsed, part, &params)) {
+            Ok(Ok(answer)) => {
+                let status = check(answers.get(day.day, input, part), &answer);
+                rows.push(row(part, Some(answer), status));
+            },
+            Ok(Err(e)) => rows.push(row(part, None, Status::Error(e))),
+            Err(msg) => rows.push(row(part, None, Status::Error(format!("panicked: {}", msg)))),
+        }
+    }
+    rows
+}
+
+pub fn verify(registry: &[Day], days: &[u8], answers: &Answers) -> Vec<Row> {
+    let mut rows = vec![];
+    for day in registry.iter().filter(|x| days.contains(&x.day)) {
+        for input in answers.inputs(day.day) {
+            rows.extend(verify_input(day, &input, answers));
+        }
+    }
+    rows
+}
+
+pub fn print_table(rows: &[Row]) {
+    let input_width = rows.iter().map(|x| x.input.len()).max().unwrap_or(0).max(5);
+    println!("{:>3}  {:>4}  {:<input_width$}  {:<7}  Answer", "Day", "Part", "Input", "Status");
+    for row in rows {
+        let answer = match &row.answer {
+            Some(answer) => answer.summary(),
+            None => String::new(),
+        };
+        let detail = match &row.status {
+            Status::Fail(expected) => format!("{} (expected {})", answer, expected.replace('\n', "/")),
+            Status::Error(msg) => msg.clone(),
+            _ => answer,
+        };
+        println!("{:>3}  {:>4}  {:<input_width$}  {:<7}  {}", row.day, row.part, row.input, row.status.to_string(), detail);
+    }
+
+    let count = |f: fn(&Status) -> bool| rows.iter().filter(|x| f(&x.status)).count();
+    println!();
+    println!("{} passed, {} failed, {} missing, {} errors",
+        count(|x| *x == Status::Pass),
+        count(|x| matches!(x, Status::Fail(_))),
+        count(|x| *x == Status::Missing),
+        count(|x| matches!(x, Status::Error(_))));
+}
+
+// Runs one part of a day on its sample input, for the tests generated by
+// days!. Missing sample answers are not a failure.
+pub fn check_sample(registry: &[Day], day: u8, part: u8) -> Result<(), String> {
+    let answers = Answers::load_all(&[day])?;
+    let day = registry.iter().find(|x| x.day == day)
+        .ok_or_else(|| format!("Day {} is not registered", day))?;
+    let row = verify_input(day, "sample_input", &answers).into_iter()
+        .find(|x| x.part == part)
+        .ok_or_else(|| format!("Invalid part: {}", part))?;
+
+    let answer = row.answer.map(|x| x.canonical()).unwrap_or_default();
+    match row.status {
+        Status::Pass | Status::Missing => Ok(()),
+        Status::Fail(expected) => Err(format!("Day {} part {} on the sample: got\n{}\nexpected\n{}", row.day, row.part, answer, expected)),
+        Status::Error(msg) => Err(msg),
+    }
+}
+
+// True when nothing regressed. Missing answers are not a failure.
+pub fn all_ok(rows: &[Row]) -> bool {
+    rows.iter().all(|x| matches!(x.status, Status::Pass | Status::Missing))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS: &str = "
+[day07.input]
+part1 = 1908462
+
+[day07.sample_input]
+part1 = 95437
+part2 = 24933642
+
+[day10.input]
+part2 = '''
+# #
+ # '''
+";
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        assert_eq!(answers.get(7, "input", 1), Some(&"1908462".to_string()));
+        assert_eq!(answers.get(7, "input", 2), None);
+        assert_eq!(answers.get(7, "sample_input", 2), Some(&"24933642".to_string()));
+        assert_eq!(answers.get(10, "input", 2), Some(&"# #\n #".to_string()));
+        assert_eq!(answers.inputs(7), vec!["input", "sample_input"]);
+        assert_eq!(answers.inputs(3), vec!["input"]);
+
+        assert!(Answers::parse("[foo.input]\npart1 = 1").is_err());
+        assert!(Answers::parse("[day01.input]\npart1 = 1.5").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        assert_eq!(check(answers.get(7, "input", 1), &Answer::from(1908462)), Status::Pass);
+        assert_eq!(check(answers.get(7, "input", 1), &Answer::from(1)), Status::Fail("1908462".to_string()));
+        assert_eq!(check(answers.get(7, "input", 2), &Answer::from(1)), Status::Missing);
+
+        let grid = Answer::Grid(vec!["# #".to_string(), " # ".to_string()]);
+        assert_eq!(check(answers.get(10, "input", 2), &grid), Status::Pass);
+    }
+
+    #[test]
+    fn test_parse_sample() {
+        let mut answers = Answers::parse(ANSWERS).unwrap();
+        answers.parse_sample(3, "part1 = 157\npart2 = 70").unwrap();
+        assert_eq!(answers.get(3, "sample_input", 1), Some(&"157".to_string()));
+        assert_eq!(answers.get(3, "sample_input", 2), Some(&"70".to_string()));
+        assert_eq!(answers.inputs(3), vec!["input", "sample_input"]);
+
+        assert!(answers.parse_sample(3, "part1 = [1]").is_err());
+    }
+
+    struct Panicky;
+
+    impl crate::solution::Solution for Panicky {
+        type Input = ();
+
+        fn parse(&self, _data: &str) -> crate::error::Result<Self::Input> {
+            Ok(())
+        }
+
+        fn part1(&self, _input: &Self::Input) -> Answer {
+            panic!("Unexpected end of input");
+        }
+
+        fn part2(&self, _input: &Self::Input) -> Answer {
+            Answer::from(24933642)
+        }
+    }
+
+    #[test]
+    fn test_verify_panic() {
+        let registry = vec![Day { day: 7, solver: &Panicky }];
+        let rows = verify(&registry, &[7], &Answers::parse(ANSWERS).unwrap());
+        let statuses: Vec<(&str, u8, &Status)> = rows.iter().map(|x| (x.input.as_str(), x.part, &x.status)).collect();
+        let panicked = Status::Error("panicked: Unexpected end of input".to_string());
+        assert_eq!(statuses, vec![
+            ("input", 1, &panicked),
+            ("input", 2, &Status::Missing),
+            ("sample_input", 1, &panicked),
+            ("sample_input", 2, &Status::Pass),
+        ]);
+    }
+
+    #[test]
+    fn test_stored_answers_file() {
+        let answers = Answers::load(&answers_path()).unwrap();
+        assert_eq!(answers.get(7, "input", 1), Some(&"1908462".to_string()));
+
+        let answers = Answers::load_all(&[7]).unwrap();
+        assert_eq!(answers.get(7, "sample_input", 1), Some(&"95437".to_string()));
+    }
+}
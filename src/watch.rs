@@ -0,0 +1,234 @@
+use std::{collections::BTreeMap, env, fs, io::{self, Write}, path::{Path, PathBuf}, process, thread, time::{Duration, Instant, SystemTime}};
+
+use serde_json::Value;
+
+use crate::bench::fmt_duration;
+use crate::cli::Options;
+use crate::input::{InputSource, day_dir};
+use crate::log::{self, Level};
+use crate::memory::{self, AllocStats};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// Moves the cursor home and clears the screen
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+fn collect(path: &Path, stamps: &mut BTreeMap<PathBuf, SystemTime>) {
+    let Ok(meta) = fs::metadata(path) else {
+        return;
+    };
+    if meta.is_dir() {
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                collect(&entry.path(), stamps);
+            }
+        }
+    } else if let Ok(modified) = meta.modified() {
+        stamps.insert(path.to_path_buf(), modified);
+    }
+}
+
+// Modification times of every file under paths. Comparing two snapshots
+// also catches files being added or removed.
+pub fn snapshot(paths: &[PathBuf]) -> BTreeMap<PathBuf, SystemTime> {
+    let mut stamps = BTreeMap::new();
+    for path in paths {
+        collect(path, &mut stamps);
+    }
+    stamps
+}
+
+// src/dayNN/ plus the input file when it lives somewhere else
+pub fn watched_paths(day: u8, input: &InputSource) -> Vec<PathBuf> {
+    let dir = day_dir(day);
+    let mut paths = vec![dir.clone()];
+    if let Some(path) = input.path(day) {
+        if !path.starts_with(&dir) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+// Arguments of the child aoc run. The child prints JSON so that the
+// answer and the timing can be shown without scraping its output.
+pub fn child_args(options: &Options) -> Vec<String> {
+    let mut args = vec![];
+    match options.targets[..] {
+        [target] => args.push(target.to_string()),
+        _ => args.push(options.targets[0].day.to_string()),
+    }
+    match &options.input {
+        InputSource::Sample => args.push("--sample".to_string()),
+        InputSource::Path(path) => {
+            // The child runs from the crate root
+            let path = std::path::absolute(path).unwrap_or_else(|_| path.clone());
+            args.push("--input".to_string());
+            args.push(path.display().to_string());
+        },
+        InputSource::Default | InputSource::Stdin => {},
+    }
+    for (name, value) in &options.params {
+        args.push("--param".to_string());
+        args.push(format!("{}={}", name, value));
+    }
+    args.push("--format".to_string());
+    args.push("json".to_string());
+    args
+}
+
+fn answer_text(answer: &Value) -> String {
+    match answer {
+        Value::String(x) => x.clone(),
+        Value::Array(rows) => rows.iter()
+            .map(|x| format!("\n{}", x.as_str().unwrap_or_default()))
+            .collect(),
+        x => x.to_string(),
+    }
+}
+
+fn memory_text(memory: &Value) -> String {
+    match (memory["allocs"].as_u64(), memory["bytes"].as_u64(), memory["peak"].as_u64()) {
+        (Some(count), Some(bytes), Some(peak)) => format!(", {}", AllocStats { count, bytes, peak }),
+        _ => String::new(),
+    }
+}
+
+// One line per day and part out of the JSON printed by the child
+pub fn report(output: &str) -> Result<Vec<String>, String> {
+    let value: Value = serde_json::from_str(output).map_err(|e| e.to_string())?;
+    let rows = value.as_array().ok_or("Expected an array of results")?;
+    let mut lines = vec![];
+    for row in rows {
+        let target = format!("d{}p{}", row["day"], row["part"]);
+        let time = fmt_duration(Duration::from_nanos(row["time_ns"].as_u64().unwrap_or(0)));
+        match row["error"].as_str() {
+            Some(error) => lines.push(format!("{}: FAILED {}", target, error)),
+            None => lines.push(format!("{}: {} ({}{})", target, answer_text(&row["answer"]), time, memory_text(&row["memory"]))),
+        }
+    }
+    Ok(lines)
+}
+
+// Rebuilds and runs the day. Compiler errors and debug output of the
+// solver go straight to stderr.
+fn run_child(args: &[String]) -> Vec<String> {
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let mut command = process::Command::new(cargo);
+    command.args(["run", "--quiet", "--release"]);
+    // Keep counting allocations if this build does
+    if memory::enabled() {
+        command.args(["--features", "alloc-stats"]);
+    }
+    let output = command
+        .arg("--")
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .stderr(process::Stdio::inherit())
+        .output();
+    let output = match output {
+        Ok(output) => output,
+        Err(e) => return vec![format!("Cannot run cargo: {}", e)],
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match report(&stdout) {
+        Ok(lines) => lines,
+        Err(_) => vec![format!("Build or run failed ({})", output.status)],
+    }
+}
+
+pub fn watch(options: &Options) -> ! {
+    let paths = watched_paths(options.targets[0].day, &options.input);
+    let names: Vec<String> = paths.iter().map(|x| x.display().to_string()).collect();
+    let mut args = child_args(options);
+    // The child's stderr is shown as is, so its debug output too
+    match log::level() {
+        Level::Quiet => {},
+        Level::Debug => args.push("-v".to_string()),
+        Level::Trace => args.push("-vv".to_string()),
+    }
+
+    let mut stamps = BTreeMap::new();
+    loop {
+        let current = snapshot(&paths);
+        if current != stamps {
+            stamps = current;
+            print!("{}", CLEAR_SCREEN);
+            println!("Watching {} (Ctrl-C to stop)", names.join(", "));
+            println!();
+            io::stdout().flush().ok();
+
+            let start = Instant::now();
+            for line in run_child(&args) {
+                println!("{}", line);
+            }
+            println!();
+            println!("Rebuilt and ran in {}", fmt_duration(start.elapsed()));
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Format, Target};
+
+    fn options(targets: Vec<Target>, input: InputSource) -> Options {
+        Options { targets, input, bench: None, save_baseline: false, all: false, format: Format::Text, params: vec![] }
+    }
+
+    #[test]
+    fn test_child_args() {
+        let args = child_args(&options(vec![Target::new(14, 2)], InputSource::Default));
+        assert_eq!(args, vec!["d14p2", "--format", "json"]);
+
+        let args = child_args(&options(vec![Target::new(14, 1), Target::new(14, 2)], InputSource::Sample));
+        assert_eq!(args, vec!["14", "--sample", "--format", "json"]);
+
+        let mut with_params = options(vec![Target::new(6, 1)], InputSource::Default);
+        with_params.params.push(("part1_window".to_string(), "5".to_string()));
+        assert_eq!(child_args(&with_params), vec!["d6p1", "--param", "part1_window=5", "--format", "json"]);
+    }
+
+    #[test]
+    fn test_watched_paths() {
+        assert_eq!(watched_paths(14, &InputSource::Default), vec![day_dir(14)]);
+        let other = PathBuf::from("/tmp/other.txt");
+        assert_eq!(watched_paths(14, &InputSource::Path(other.clone())), vec![day_dir(14), other]);
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let dir = env::temp_dir().join(format!("aoc_watch_{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.txt"), "1").unwrap();
+        let before = snapshot(std::slice::from_ref(&dir));
+        assert_eq!(before.len(), 1);
+
+        fs::write(dir.join("b.txt"), "2").unwrap();
+        let after = snapshot(std::slice::from_ref(&dir));
+        fs::remove_dir_all(&dir).unwrap();
+        assert_ne!(before, after);
+    }
+
+    #[test]
+    fn test_report() {
+        let output = r##"[
+            {"day": 14, "part": 1, "answer": 913, "time_ns": 1500, "error": null, "input": "x"},
+            {"day": 3, "part": 1, "answer": 157, "time_ns": 1500, "error": null, "input": "x",
+             "memory": {"allocs": 12, "bytes": 4096, "peak": 512}},
+            {"day": 5, "part": 1, "answer": "CMZ", "time_ns": 2500000, "error": null, "input": "x"},
+            {"day": 10, "part": 2, "answer": ["# ", " #"], "time_ns": 0, "error": null, "input": "x"},
+            {"day": 14, "part": 2, "answer": null, "time_ns": 0, "error": "panicked: oops", "input": "x"}
+        ]"##;
+        assert_eq!(report(output).unwrap(), vec![
+            "d14p1: 913 (1.5µs)",
+            "d3p1: 157 (1.5µs, 12 allocs, 4.0KB total, 512B peak)",
+            "d5p1: CMZ (2.500ms)",
+            "d10p2: \n# \n # (0.0µs)",
+            "d14p2: FAILED panicked: oops",
+        ]);
+        assert!(report("error: could not compile").is_err());
+    }
+}